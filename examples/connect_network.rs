@@ -1,7 +1,11 @@
 use std::time::Duration;
 
 use clap::Parser;
-use iwdrs::{agent::Agent, network::Network};
+use iwdrs::{
+    agent::Agent,
+    network::{Network, NetworkInfo},
+    signal_strength::SignalStrength,
+};
 
 #[derive(Debug, Parser)]
 /// Connect to a Wifi Network given a SSID and optionally a password.
@@ -19,33 +23,32 @@ async fn main() {
     let agent = PasswdAgent(password);
     let _agent_manager = session.register_agent(agent).await.unwrap();
 
-    let station = session.stations().await.unwrap().pop().unwrap();
-    let mut networks = station.discovered_networks().await.unwrap();
+    let station = session.primary_station().await.unwrap().unwrap();
+    let mut networks = station.discovered_networks_info().await.unwrap();
 
-    let network = match find_network(&ssid, &networks).await {
+    let network = match find_network(&ssid, &networks) {
         Some(network) => network,
         None => {
             station.scan().await.unwrap();
             while station.is_scanning().await.unwrap() {
                 tokio::time::sleep(Duration::from_millis(100)).await;
             }
-            networks = station.discovered_networks().await.unwrap();
-            find_network(&ssid, &networks)
-                .await
-                .expect("Could not find network")
+            networks = station.discovered_networks_info().await.unwrap();
+            find_network(&ssid, &networks).expect("Could not find network")
         }
     };
 
     network.connect().await.unwrap()
 }
 
-async fn find_network<'a>(ssid: &str, networks: &'a [(Network, i16)]) -> Option<&'a Network> {
-    for (network, _signal_strength) in networks {
-        if network.name().await.unwrap() == ssid {
-            return Some(network);
-        }
-    }
-    None
+fn find_network<'a>(
+    ssid: &str,
+    networks: &'a [(NetworkInfo, SignalStrength)],
+) -> Option<&'a Network> {
+    networks
+        .iter()
+        .find(|(info, _signal_strength)| info.name == ssid)
+        .map(|(info, _signal_strength)| &info.network)
 }
 
 struct PasswdAgent(Option<String>);
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+use futures_lite::StreamExt;
+use iwdrs::hotspot::guest::GuestHotspot;
+
+/// Start a guest access point on the first device in access point mode, with a passphrase changing every hour.
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let session = iwdrs::session::Session::new().await.unwrap();
+    let access_point = session.access_points().await.unwrap().pop().unwrap();
+
+    let hotspot = GuestHotspot::start(access_point, "guests").await.unwrap();
+    println!("{}", hotspot.credentials());
+
+    let mut rotations = hotspot.rotations(Duration::from_secs(3600));
+    while let Some(credentials) = rotations.next().await {
+        match credentials {
+            Ok(credentials) => println!("{credentials}"),
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+}
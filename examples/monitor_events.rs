@@ -0,0 +1,11 @@
+use futures_lite::StreamExt;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let session = iwdrs::session::Session::new().await.unwrap();
+
+    let mut events = session.events().await.unwrap();
+    while let Some(event) = events.next().await {
+        println!("{:?}", event);
+    }
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use futures_lite::StreamExt;
+
+/// Print the clients joining and leaving the first access point.
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let session = iwdrs::session::Session::new().await.unwrap();
+
+    let access_point_diagnostics = session
+        .access_points_diagnostics()
+        .await
+        .unwrap()
+        .pop()
+        .unwrap();
+
+    let mut events = access_point_diagnostics
+        .client_events(Duration::from_secs(2))
+        .await
+        .unwrap();
+    while let Some(event) = events.next().await {
+        println!("{:?}", event);
+    }
+}
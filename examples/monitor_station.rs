@@ -4,7 +4,12 @@ use std::time::Duration;
 async fn main() {
     let session = iwdrs::session::Session::new().await.unwrap();
 
-    let station_diagnostics = session.stations_diagnostics().await.unwrap().pop().unwrap();
+    let station = session.primary_station().await.unwrap().unwrap();
+    let station_diagnostics = session
+        .station_diagnostics_for(&station)
+        .await
+        .unwrap()
+        .unwrap();
 
     let mut ticker = tokio::time::interval(Duration::from_secs(1));
     loop {
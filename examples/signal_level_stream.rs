@@ -0,0 +1,37 @@
+use clap::Parser;
+use futures_lite::StreamExt;
+
+#[derive(Debug, Parser)]
+/// Print the signal level changes of the connected network from two independent subscribers.
+struct Args {
+    #[clap(allow_hyphen_values = true)]
+    levels: Vec<i16>,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let Args { levels } = Args::parse();
+
+    let session = iwdrs::session::Session::new().await.unwrap();
+
+    let station = session.primary_station().await.unwrap().unwrap();
+
+    let mut tray = station.signal_level_stream(levels).await.unwrap();
+    let mut log = tray.clone();
+
+    let tray = async {
+        while let Some(level) = tray.next().await {
+            println!("[tray] level {}", level.index);
+        }
+    };
+    let log = async {
+        while let Some(level) = log.next().await {
+            println!(
+                "[log] Wifi Signal Strength Min {:?} Max {:?}",
+                level.min_dbm, level.max_dbm
+            );
+        }
+    };
+
+    futures_lite::future::zip(tray, log).await;
+}
@@ -1,17 +1,39 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use futures_lite::Stream;
 use zvariant::{OwnedObjectPath, Value};
 
-use zbus::{Connection, Proxy};
+use zbus::Proxy;
 
 use crate::{
+    Error,
+    daemon::Daemon,
     error::{
         Result as IWDResult,
         access_point::{AccessPointStartError, AccessPointStopError, ScanError, StartProfileError},
     },
-    iwd_interface::iwd_interface_impl,
+    iwd_interface::{
+        IwdInterface, Properties, get_all, iwd_interface_impl, optional_property, property,
+    },
+    network::NetworkType,
+    radio::Channel,
+    signal_strength::SignalStrength,
 };
 
-iwd_interface_impl!(AccessPoint, "net.connman.iwd.AccessPoint");
+pub mod diagnostics;
+pub mod profile;
+
+use diagnostics::{ClientDiagnostics, ClientEvent};
+use profile::ApProfile;
+
+iwd_interface_impl!(AccessPoint, "net.connman.iwd.AccessPoint", {
+    Started: bool => has_started, started_stream;
+    optional Frequency: u32 => frequency, frequency_stream;
+    Scanning: bool => is_scanning, scanning_stream;
+    optional Name: String => name, name_stream;
+    optional PairwiseCiphers: Vec<String> => pairwise_ciphers, pairwise_ciphers_stream;
+    optional GroupCipher: String => group_cipher, group_cipher_stream;
+});
 
 impl AccessPoint {
     // Methods
@@ -30,52 +52,83 @@ impl AccessPoint {
         Ok(())
     }
 
+    /// Write the profile into iwd's state directory, then start the access point from it. Writing the profile
+    /// usually requires root privileges.
+    pub async fn start_with_profile(
+        &self,
+        profile: &ApProfile,
+    ) -> IWDResult<(), StartProfileError> {
+        let write_profile = async {
+            let daemon = Daemon::new(
+                self.proxy.connection().clone(),
+                OwnedObjectPath::try_from("/net/connman/iwd")?,
+            )
+            .await?;
+            let state_dir = daemon.get_info().await?.state_dir;
+            profile.write(&state_dir)
+        };
+        write_profile.await.map_err(Error::into_operation)?;
+
+        self.start_profile(profile.ssid()).await
+    }
+
     pub async fn scan(&self) -> IWDResult<(), ScanError> {
         self.proxy.call_method("Scan", &()).await?;
         Ok(())
     }
 
-    pub async fn networks(&self) -> zbus::Result<Vec<HashMap<String, String>>> {
+    /// Networks found by the last [`AccessPoint::scan`], strongest first.
+    pub async fn networks(&self) -> crate::Result<Vec<ScannedNetwork>> {
         let networks = self.proxy.call_method("GetOrderedNetworks", &()).await?;
         let body = networks.body();
         let body: Vec<HashMap<String, Value>> = body.deserialize()?;
-        let body = body
-            .into_iter()
-            .map(|map| {
-                map.into_iter()
-                    .map(|(k, v)| (k, v.to_string()))
-                    .collect::<HashMap<String, String>>()
-            })
-            .collect::<Vec<HashMap<String, String>>>();
-
-        Ok(body)
-    }
 
-    // Proprieties
-    pub async fn has_started(&self) -> zbus::Result<bool> {
-        let has_started: bool = self.proxy.get_property("Started").await?;
-        Ok(has_started)
+        body.into_iter()
+            .map(ScannedNetwork::from_zbus_map)
+            .collect()
     }
 
-    pub async fn frequency(&self) -> zbus::Result<Option<u32>> {
-        Ok(self.proxy.get_property("Frequency").await.ok())
+    /// The channel the access point operates on, `None` while it is not started.
+    pub async fn channel(&self) -> crate::Result<Option<Channel>> {
+        Ok(self.frequency().await?.and_then(Channel::from_frequency))
     }
 
-    pub async fn is_scanning(&self) -> zbus::Result<bool> {
-        let is_scanning: bool = self.proxy.get_property("Scanning").await?;
-        Ok(is_scanning)
+    /// Fetch all the properties of the access point in a single call.
+    pub async fn info(&self) -> crate::Result<AccessPointInfo> {
+        AccessPointInfo::from_properties(self.clone(), &get_all(&self.proxy).await?)
     }
+}
 
-    pub async fn name(&self) -> zbus::Result<Option<String>> {
-        Ok(self.proxy.get_property("Name").await.ok())
-    }
+/// Snapshot of the properties of an [`AccessPoint`].
+#[derive(Debug, Clone)]
+pub struct AccessPointInfo {
+    pub access_point: AccessPoint,
+    pub started: bool,
+    pub name: Option<String>,
+    pub frequency: Option<u32>,
+    pub scanning: bool,
+    pub pairwise_ciphers: Option<Vec<String>>,
+    pub group_cipher: Option<String>,
+}
 
-    pub async fn pairwise_ciphers(&self) -> zbus::Result<Option<Vec<String>>> {
-        Ok(self.proxy.get_property("PairwiseCiphers").await.ok())
+impl AccessPointInfo {
+    pub(crate) fn from_properties(
+        access_point: AccessPoint,
+        properties: &Properties,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            access_point,
+            started: property(properties, "Started")?,
+            name: optional_property(properties, "Name")?,
+            frequency: optional_property(properties, "Frequency")?,
+            scanning: property(properties, "Scanning")?,
+            pairwise_ciphers: optional_property(properties, "PairwiseCiphers")?,
+            group_cipher: optional_property(properties, "GroupCipher")?,
+        })
     }
 
-    pub async fn group_cipher(&self) -> zbus::Result<Option<String>> {
-        Ok(self.proxy.get_property("GroupCipher").await.ok())
+    pub fn channel(&self) -> Option<Channel> {
+        self.frequency.and_then(Channel::from_frequency)
     }
 }
 
@@ -85,20 +138,57 @@ iwd_interface_impl!(
 );
 
 impl AccessPointDiagnostics {
-    pub async fn get(&self) -> zbus::Result<Vec<HashMap<String, String>>> {
+    /// Stream of the clients joining, leaving and whose link changes, detected by polling the diagnostics every
+    /// `interval` while the access point is started.
+    pub async fn client_events(
+        &self,
+        interval: Duration,
+    ) -> crate::Result<impl Stream<Item = crate::Result<ClientEvent>> + Unpin + Send + 'static>
+    {
+        diagnostics::client_events(self.clone(), interval).await
+    }
+
+    /// Diagnostics of every client connected to the access point.
+    pub async fn get(&self) -> crate::Result<Vec<ClientDiagnostics>> {
         let diagnostic = self.proxy.call_method("GetDiagnostics", &()).await?;
 
         let body = diagnostic.body();
         let body: Vec<HashMap<String, Value>> = body.deserialize()?;
-        let body = body
-            .into_iter()
-            .map(|map| {
-                map.into_iter()
-                    .map(|(k, v)| (k, v.to_string()))
-                    .collect::<HashMap<String, String>>()
-            })
-            .collect::<Vec<HashMap<String, String>>>();
-
-        Ok(body)
+
+        body.into_iter()
+            .map(ClientDiagnostics::from_zbus_map)
+            .collect()
+    }
+}
+
+/// A network found by [`AccessPoint::scan`].
+#[derive(Debug, Clone)]
+pub struct ScannedNetwork {
+    pub name: String,
+    pub signal_strength: SignalStrength,
+    pub network_type: NetworkType,
+}
+
+impl ScannedNetwork {
+    fn from_zbus_map(body: HashMap<String, Value>) -> crate::Result<Self> {
+        let name: String = body
+            .get("Name")
+            .ok_or_else(|| crate::Error::missing_property("Name"))?
+            .try_into()?;
+        let signal_strength: i16 = body
+            .get("SignalStrength")
+            .ok_or_else(|| crate::Error::missing_property("SignalStrength"))?
+            .try_into()?;
+        let network_type: String = body
+            .get("Type")
+            .ok_or_else(|| crate::Error::missing_property("Type"))?
+            .try_into()?;
+
+        Ok(Self {
+            name,
+            signal_strength: SignalStrength::from_centi_dbm(signal_strength),
+            network_type: NetworkType::from_str(&network_type)
+                .unwrap_or(NetworkType::Unknown(network_type)),
+        })
     }
 }
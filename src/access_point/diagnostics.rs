@@ -0,0 +1,201 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    time::Duration,
+};
+
+use futures_lite::{Stream, StreamExt, future, stream};
+use zvariant::Value;
+
+use crate::{
+    access_point::{AccessPoint, AccessPointDiagnostics},
+    iwd_interface::IwdInterface,
+    mac_address::MacAddress,
+    signal_strength::SignalStrength,
+    station::diagnostics::{Mode, some_try_into},
+    timer,
+};
+
+/// Diagnostics of a client connected to the access point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientDiagnostics {
+    pub address: MacAddress,
+    pub rssi: Option<SignalStrength>,
+    pub average_rssi: Option<SignalStrength>,
+    pub rx_mode: Option<Mode>,
+    pub rx_rate_kbps: Option<u64>,
+    pub rx_mcs: Option<u8>,
+    pub tx_mode: Option<Mode>,
+    pub tx_rate_kbps: Option<u64>,
+    pub tx_mcs: Option<u8>,
+    pub inactive_time: Option<Duration>,
+    pub connected_time: Option<Duration>,
+}
+
+impl ClientDiagnostics {
+    pub(crate) fn from_zbus_map(body: HashMap<String, Value>) -> crate::Result<Self> {
+        Ok(Self {
+            address: body
+                .get("Address")
+                .ok_or_else(|| crate::Error::missing_property("Address"))?
+                .try_into()?,
+            rssi: some_try_into!(body, "RSSI").map(SignalStrength::from_dbm),
+            average_rssi: some_try_into!(body, "AverageRSSI").map(SignalStrength::from_dbm),
+            rx_mode: some_try_into!(body, "RxMode"),
+            rx_rate_kbps: some_try_into!(body, "RxBitrate")
+                .map(|rate_100_kpbs: u32| 100 * u64::from(rate_100_kpbs)),
+            rx_mcs: some_try_into!(body, "RxMCS"),
+            tx_mode: some_try_into!(body, "TxMode"),
+            tx_rate_kbps: some_try_into!(body, "TxBitrate")
+                .map(|rate_100_kpbs: u32| 100 * u64::from(rate_100_kpbs)),
+            tx_mcs: some_try_into!(body, "TxMCS"),
+            inactive_time: some_try_into!(body, "InactiveTime")
+                .map(|inactive_time_ms: u32| Duration::from_millis(u64::from(inactive_time_ms))),
+            connected_time: some_try_into!(body, "ConnectedTime")
+                .map(|connected_time_s: u32| Duration::from_secs(u64::from(connected_time_s))),
+        })
+    }
+
+    /// Whether the quality of the link changed, ignoring the counters that change on every poll.
+    fn link_changed(&self, other: &Self) -> bool {
+        self.rssi != other.rssi
+            || self.rx_mode != other.rx_mode
+            || self.rx_rate_kbps != other.rx_rate_kbps
+            || self.rx_mcs != other.rx_mcs
+            || self.tx_mode != other.tx_mode
+            || self.tx_rate_kbps != other.tx_rate_kbps
+            || self.tx_mcs != other.tx_mcs
+    }
+}
+
+/// A change in the clients of an access point, see [`AccessPointDiagnostics::client_events`].
+#[derive(Debug, Clone)]
+pub enum ClientEvent {
+    ClientJoined(ClientDiagnostics),
+    /// Also sent for every client when the access point stops.
+    ClientLeft {
+        address: MacAddress,
+    },
+    /// The signal strength, rates or modes of the client changed.
+    ClientUpdated(ClientDiagnostics),
+}
+
+pub(crate) type ClientEventStream = Pin<Box<dyn Stream<Item = crate::Result<ClientEvent>> + Send>>;
+
+struct ClientWatcher<S> {
+    diagnostics: AccessPointDiagnostics,
+    started_stream: S,
+    interval: Duration,
+    started: bool,
+    poll_now: bool,
+    clients: HashMap<MacAddress, ClientDiagnostics>,
+    pending: VecDeque<ClientEvent>,
+}
+
+impl<S: Stream<Item = crate::Result<bool>> + Unpin> ClientWatcher<S> {
+    async fn next(&mut self) -> Option<crate::Result<ClientEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+
+            if !self.started {
+                match self.started_stream.next().await? {
+                    Ok(started) => self.set_started(started),
+                    Err(err) => return Some(Err(err)),
+                }
+                continue;
+            }
+
+            if !self.poll_now {
+                let interval = self.interval;
+                let tick = async {
+                    timer::sleep(interval).await;
+                    None
+                };
+                let started_change = async { Some(self.started_stream.next().await) };
+                match future::or(started_change, tick).await {
+                    Some(Some(Ok(started))) => {
+                        self.set_started(started);
+                        continue;
+                    }
+                    Some(Some(Err(err))) => return Some(Err(err)),
+                    Some(None) => return None,
+                    None => {}
+                }
+            }
+
+            self.poll_now = false;
+            match self.diagnostics.get().await {
+                Ok(clients) => self.update(clients),
+                // iwd removes the diagnostics interface as soon as the access point stops, possibly before the
+                // change of `Started` is received.
+                Err(crate::Error::ObjectVanished) => self.set_started(false),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+
+    fn set_started(&mut self, started: bool) {
+        if started && !self.started {
+            self.poll_now = true;
+        }
+        if !started {
+            self.update(Vec::new());
+        }
+        self.started = started;
+    }
+
+    fn update(&mut self, clients: Vec<ClientDiagnostics>) {
+        let mut previous_clients = std::mem::take(&mut self.clients);
+
+        for client in clients {
+            match previous_clients.remove(&client.address) {
+                None => self
+                    .pending
+                    .push_back(ClientEvent::ClientJoined(client.clone())),
+                Some(previous) if previous.link_changed(&client) => {
+                    self.pending
+                        .push_back(ClientEvent::ClientUpdated(client.clone()));
+                }
+                Some(_) => {}
+            }
+            self.clients.insert(client.address, client);
+        }
+
+        self.pending.extend(
+            previous_clients
+                .into_keys()
+                .map(|address| ClientEvent::ClientLeft { address }),
+        );
+    }
+}
+
+pub(crate) async fn client_events(
+    diagnostics: AccessPointDiagnostics,
+    interval: Duration,
+) -> crate::Result<ClientEventStream> {
+    let access_point = AccessPoint::new(
+        diagnostics.proxy.connection().clone(),
+        diagnostics.object_path(),
+    )
+    .await?;
+
+    let watcher = ClientWatcher {
+        diagnostics,
+        started_stream: access_point.started_stream().await?,
+        interval,
+        started: false,
+        poll_now: false,
+        clients: HashMap::new(),
+        pending: VecDeque::new(),
+    };
+
+    Ok(Box::pin(stream::unfold(
+        watcher,
+        |mut watcher| async move {
+            let event = watcher.next().await?;
+            Some((event, watcher))
+        },
+    )))
+}
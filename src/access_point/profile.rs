@@ -0,0 +1,336 @@
+use std::{
+    fmt::{Display, Write as _},
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    Error,
+    radio::{Band, Channel},
+    settings::{escape_value, is_encodable, write_secret_file},
+};
+
+/// An access point profile, written to `<state directory>/ap/<ssid>.ap` for
+/// [`crate::access_point::AccessPoint::start_profile`]. See `iwd.ap(5)` for the meaning of each setting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApProfile {
+    ssid: String,
+    security: Option<ApSecurity>,
+    channel: Option<u16>,
+    ipv4: Option<ApIpv4>,
+    wsc: Option<ApWsc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ApSecurity {
+    Passphrase(String),
+    PreSharedKey(String),
+}
+
+/// `[IPv4]` settings, used by iwd's DHCP server when network configuration is enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApIpv4 {
+    pub address: Ipv4Addr,
+    pub gateway: Option<Ipv4Addr>,
+    pub netmask: Option<Ipv4Addr>,
+    pub dns: Vec<Ipv4Addr>,
+    /// Lease duration in seconds.
+    pub lease_time: Option<u32>,
+    /// First and last address handed out to clients.
+    pub lease_range: Option<(Ipv4Addr, Ipv4Addr)>,
+}
+
+/// `[WSC]` settings, advertised to clients using Wi-Fi Protected Setup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApWsc {
+    pub device_name: Option<String>,
+    pub primary_device_type: Option<String>,
+}
+
+impl ApProfile {
+    pub fn new(ssid: impl Into<String>) -> Self {
+        Self {
+            ssid: ssid.into(),
+            security: None,
+            channel: None,
+            ipv4: None,
+            wsc: None,
+        }
+    }
+
+    pub fn ssid(&self) -> &str {
+        &self.ssid
+    }
+
+    /// Protect the access point with a passphrase of 8 to 63 printable ASCII characters.
+    pub fn passphrase(mut self, passphrase: impl Into<String>) -> Self {
+        self.security = Some(ApSecurity::Passphrase(passphrase.into()));
+        self
+    }
+
+    /// Protect the access point with a 64 hex digits pre-shared key instead of a passphrase.
+    pub fn pre_shared_key(mut self, psk: impl Into<String>) -> Self {
+        self.security = Some(ApSecurity::PreSharedKey(psk.into()));
+        self
+    }
+
+    /// 2.4 or 5 GHz channel number, iwd picks one if unset.
+    pub fn channel(mut self, channel: u16) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    pub fn ipv4(mut self, ipv4: ApIpv4) -> Self {
+        self.ipv4 = Some(ipv4);
+        self
+    }
+
+    pub fn wsc(mut self, wsc: ApWsc) -> Self {
+        self.wsc = Some(wsc);
+        self
+    }
+
+    /// Check the settings against the constraints iwd enforces when loading the profile.
+    pub fn validate(&self) -> crate::Result<()> {
+        let invalid = |message: &str| Err(Error::InvalidArgument(message.to_string()));
+
+        if self.ssid.is_empty() || self.ssid.len() > 32 {
+            return invalid("the SSID must be 1 to 32 bytes long");
+        }
+        if self.ssid.contains('/') || self.ssid.starts_with('.') {
+            return invalid("the SSID can not be used as a file name");
+        }
+
+        match &self.security {
+            Some(ApSecurity::Passphrase(passphrase)) => {
+                if !(8..=63).contains(&passphrase.len())
+                    || !passphrase.bytes().all(|byte| (0x20..=0x7e).contains(&byte))
+                {
+                    return invalid("the passphrase must be 8 to 63 printable ASCII characters");
+                }
+            }
+            Some(ApSecurity::PreSharedKey(psk)) => {
+                if psk.len() != 64 || !psk.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                    return invalid("the pre-shared key must be 64 hexadecimal digits");
+                }
+            }
+            None => return invalid("a passphrase or a pre-shared key is required"),
+        }
+
+        if let Some(number) = self.channel {
+            let valid = [Band::Ghz2_4, Band::Ghz5]
+                .into_iter()
+                .any(|band| Channel { band, number }.frequency().is_some());
+            if !valid {
+                return invalid("the channel is not a 2.4 or 5 GHz channel");
+            }
+        }
+
+        if let Some(wsc) = &self.wsc {
+            let values = [&wsc.device_name, &wsc.primary_device_type];
+            if !values
+                .into_iter()
+                .flatten()
+                .all(|value| is_encodable(value))
+            {
+                return invalid("the WSC settings can not contain control characters");
+            }
+            if values
+                .into_iter()
+                .flatten()
+                .any(|value| value.contains('\n') || value.contains('\r'))
+            {
+                return invalid("the WSC settings must fit on one line");
+            }
+        }
+
+        if let Some(ApIpv4 {
+            lease_range: Some((first, last)),
+            ..
+        }) = &self.ipv4
+            && first > last
+        {
+            return invalid("the lease range ends before it starts");
+        }
+
+        Ok(())
+    }
+
+    /// Path of the profile inside iwd's state directory, see [`crate::daemon::DaemonInfo::state_dir`].
+    pub fn path(&self, state_dir: &Path) -> PathBuf {
+        state_dir.join("ap").join(format!("{}.ap", self.ssid))
+    }
+
+    /// Validate the profile and write it, readable by its owner only, into iwd's state directory.
+    pub fn write(&self, state_dir: &Path) -> crate::Result<PathBuf> {
+        self.validate()?;
+
+        let path = self.path(state_dir);
+        write_secret_file(&path, &self.to_string())?;
+        Ok(path)
+    }
+}
+
+impl Display for ApProfile {
+    /// The content of the profile file.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(channel) = self.channel {
+            writeln!(f, "[General]\nChannel={channel}\n")?;
+        }
+
+        match &self.security {
+            Some(ApSecurity::Passphrase(passphrase)) => {
+                writeln!(f, "[Security]\nPassphrase={}\n", escape_value(passphrase))?
+            }
+            Some(ApSecurity::PreSharedKey(psk)) => writeln!(f, "[Security]\nPreSharedKey={psk}\n")?,
+            None => {}
+        }
+
+        if let Some(ipv4) = &self.ipv4 {
+            writeln!(f, "[IPv4]\nAddress={}", ipv4.address)?;
+            if let Some(gateway) = ipv4.gateway {
+                writeln!(f, "Gateway={gateway}")?;
+            }
+            if let Some(netmask) = ipv4.netmask {
+                writeln!(f, "Netmask={netmask}")?;
+            }
+            if !ipv4.dns.is_empty() {
+                let mut dns_list = String::new();
+                for (i, dns) in ipv4.dns.iter().enumerate() {
+                    if i > 0 {
+                        dns_list.push(',');
+                    }
+                    write!(dns_list, "{dns}")?;
+                }
+                writeln!(f, "DNSList={dns_list}")?;
+            }
+            if let Some(lease_time) = ipv4.lease_time {
+                writeln!(f, "LeaseTime={lease_time}")?;
+            }
+            if let Some((first, last)) = ipv4.lease_range {
+                writeln!(f, "IPRange={first},{last}")?;
+            }
+            writeln!(f)?;
+        }
+
+        if let Some(wsc) = &self.wsc {
+            writeln!(f, "[WSC]")?;
+            if let Some(device_name) = &wsc.device_name {
+                writeln!(f, "DeviceName={}", escape_value(device_name))?;
+            }
+            if let Some(primary_device_type) = &wsc.primary_device_type {
+                writeln!(f, "PrimaryDeviceType={}", escape_value(primary_device_type))?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::{ApIpv4, ApProfile, ApWsc};
+
+    #[test]
+    fn renders_every_section() {
+        let profile = ApProfile::new("MyAP")
+            .passphrase("correct horse")
+            .channel(6)
+            .ipv4(ApIpv4 {
+                address: Ipv4Addr::new(192, 168, 80, 1),
+                gateway: None,
+                netmask: Some(Ipv4Addr::new(255, 255, 255, 0)),
+                dns: vec![Ipv4Addr::new(1, 1, 1, 1), Ipv4Addr::new(9, 9, 9, 9)],
+                lease_time: Some(3600),
+                lease_range: Some((
+                    Ipv4Addr::new(192, 168, 80, 10),
+                    Ipv4Addr::new(192, 168, 80, 50),
+                )),
+            })
+            .wsc(ApWsc {
+                device_name: Some("Guest AP".to_string()),
+                primary_device_type: None,
+            });
+
+        assert!(profile.validate().is_ok());
+        assert_eq!(
+            profile.to_string(),
+            "[General]\nChannel=6\n\n\
+             [Security]\nPassphrase=correct horse\n\n\
+             [IPv4]\nAddress=192.168.80.1\nNetmask=255.255.255.0\nDNSList=1.1.1.1,9.9.9.9\nLeaseTime=3600\n\
+             IPRange=192.168.80.10,192.168.80.50\n\n\
+             [WSC]\nDeviceName=Guest AP\n\n"
+        );
+    }
+
+    #[test]
+    fn escapes_values() {
+        let profile = ApProfile::new("MyAP")
+            .passphrase(" back\\slash")
+            .wsc(ApWsc {
+                device_name: Some("tab\tbed".to_string()),
+                primary_device_type: None,
+            });
+
+        assert!(profile.validate().is_ok());
+        assert_eq!(
+            profile.to_string(),
+            "[Security]\nPassphrase=\\sback\\\\slash\n\n[WSC]\nDeviceName=tab\\tbed\n\n"
+        );
+    }
+
+    #[test]
+    fn rejects_values_that_can_not_be_written() {
+        let with_device_name = |device_name: &str| {
+            ApProfile::new("MyAP").passphrase("12345678").wsc(ApWsc {
+                device_name: Some(device_name.to_string()),
+                primary_device_type: None,
+            })
+        };
+        assert!(with_device_name("line\nInjected=1").validate().is_err());
+        assert!(with_device_name("nul\0").validate().is_err());
+
+        assert!(
+            ApProfile::new("MyAP")
+                .passphrase("new\nline!")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ApProfile::new("MyAP")
+                .passphrase("short")
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ApProfile::new("a/b")
+                .passphrase("12345678")
+                .validate()
+                .is_err()
+        );
+        assert!(ApProfile::new("MyAP").validate().is_err());
+        assert!(
+            ApProfile::new("MyAP")
+                .pre_shared_key("00".repeat(32))
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            ApProfile::new("MyAP")
+                .passphrase("12345678")
+                .channel(14)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            ApProfile::new("MyAP")
+                .passphrase("12345678")
+                .channel(15)
+                .validate()
+                .is_err()
+        );
+    }
+}
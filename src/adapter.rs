@@ -1,34 +1,150 @@
-use zbus::{Connection, Proxy, Result};
-use zvariant::OwnedObjectPath;
+use std::time::Duration;
 
-use crate::iwd_interface::iwd_interface_impl;
+use futures_lite::{Stream, StreamExt};
+use zbus::Proxy;
 
-iwd_interface_impl!(Adapter, "net.connman.iwd.Adapter");
+use crate::{
+    Error, Result,
+    error::adapter::SetPowerError,
+    iwd_interface::{Properties, get_all, iwd_interface_impl, optional_property, property},
+    rfkill::{self, BlockState, RfkillEvent, RfkillSwitch},
+    timer, unblock,
+};
+
+// iwd notices the end of a soft block asynchronously, and refuses to power the adapter on until then.
+const UNBLOCK_RETRIES: u32 = 20;
+const UNBLOCK_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+iwd_interface_impl!(Adapter, "net.connman.iwd.Adapter", {
+    Name: String => name, name_stream;
+    optional Model: String => model, model_stream;
+    optional Vendor: String => vendor, vendor_stream;
+    SupportedModes: Vec<String> => supported_modes, supported_modes_stream;
+    Powered: bool => is_powered, powered_stream, set_power -> SetPowerError;
+});
 
 impl Adapter {
-    pub async fn name(&self) -> Result<String> {
-        self.proxy.get_property("Name").await
+    /// The kill switches blocking the adapter: its own and the ones blocking every wireless adapter.
+    pub async fn rfkill_switches(&self) -> Result<Vec<RfkillSwitch>> {
+        let phy = self.name().await?;
+        unblock::unblock(move || rfkill::switches_for_phy(&phy)).await
     }
 
-    pub async fn model(&self) -> Result<String> {
-        self.proxy.get_property("Model").await
+    pub async fn block_state(&self) -> Result<BlockState> {
+        Ok(rfkill::block_state(&self.rfkill_switches().await?))
     }
 
-    pub async fn vendor(&self) -> Result<String> {
-        self.proxy.get_property("Vendor").await
+    /// The block state of the adapter, then its changes.
+    pub async fn block_state_stream(
+        &self,
+    ) -> Result<impl Stream<Item = Result<BlockState>> + Unpin + Send + 'static> {
+        let phy = self.name().await?;
+        let mut switches = Vec::<RfkillSwitch>::new();
+        let mut last_state = None;
+
+        // `/dev/rfkill` starts with an `Added` event for every existing switch.
+        Ok(rfkill::events()?.filter_map(move |event| {
+            match event {
+                Ok(RfkillEvent::Added(switch) | RfkillEvent::Changed(switch)) => {
+                    switches.retain(|known| known.index != switch.index);
+                    if switch.affects_phy(&phy) {
+                        switches.push(switch);
+                    }
+                }
+                Ok(RfkillEvent::Removed { index }) => switches.retain(|known| known.index != index),
+                Err(err) => return Some(Err(err)),
+            }
+
+            let state = rfkill::block_state(&switches);
+            (last_state != Some(state)).then(|| {
+                last_state = Some(state);
+                Ok(state)
+            })
+        }))
     }
 
-    pub async fn supported_modes(&self) -> Result<Vec<String>> {
-        self.proxy.get_property("SupportedModes").await
+    /// Power the adapter on, telling rfkill blocks apart from other failures. With `unblock`, a soft block is
+    /// cleared first, which requires write access to `/sys/class/rfkill`.
+    pub async fn power_on(&self, unblock: bool) -> Result<(), SetPowerError> {
+        let switches = self
+            .rfkill_switches()
+            .await
+            .map_err(Error::into_operation)?;
+        let blocked = |state| {
+            switches
+                .iter()
+                .filter(|switch| switch.state() == state)
+                .map(|switch| switch.name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        let hard_blocked = blocked(BlockState::HardBlocked);
+        if !hard_blocked.is_empty() {
+            return Err(Error::Operation {
+                error: SetPowerError::HardBlocked,
+                description: Some(format!("blocked by {}", hard_blocked.join(", "))),
+            });
+        }
+
+        let soft_blocked = blocked(BlockState::SoftBlocked);
+        if soft_blocked.is_empty() {
+            return self.set_power(true).await;
+        }
+        if !unblock {
+            return Err(Error::Operation {
+                error: SetPowerError::SoftBlocked,
+                description: Some(format!("blocked by {}", soft_blocked.join(", "))),
+            });
+        }
+
+        let soft_blocked = switches
+            .into_iter()
+            .filter(|switch| switch.soft_blocked)
+            .collect::<Vec<_>>();
+        unblock::unblock(move || soft_blocked.iter().try_for_each(RfkillSwitch::unblock))
+            .await
+            .map_err(Error::into_operation)?;
+        let mut retries = 0;
+        loop {
+            match self.set_power(true).await {
+                Err(Error::Operation {
+                    error: SetPowerError::NotAvailable | SetPowerError::Busy,
+                    ..
+                }) if retries < UNBLOCK_RETRIES => {
+                    retries += 1;
+                    timer::sleep(UNBLOCK_RETRY_INTERVAL).await;
+                }
+                result => return result,
+            }
+        }
     }
 
-    pub async fn is_powered(&self) -> Result<bool> {
-        let is_powered: bool = self.proxy.get_property("Powered").await?;
-        Ok(is_powered)
+    /// Fetch all the properties of the adapter in a single call.
+    pub async fn info(&self) -> Result<AdapterInfo> {
+        AdapterInfo::from_properties(self.clone(), &get_all(&self.proxy).await?)
     }
+}
+
+/// Snapshot of the properties of an [`Adapter`].
+#[derive(Debug, Clone)]
+pub struct AdapterInfo {
+    pub adapter: Adapter,
+    pub name: String,
+    pub model: Option<String>,
+    pub vendor: Option<String>,
+    pub supported_modes: Vec<String>,
+    pub powered: bool,
+}
 
-    pub async fn set_power(&self, mode: bool) -> Result<()> {
-        self.proxy.set_property("Powered", mode).await?;
-        Ok(())
+impl AdapterInfo {
+    pub(crate) fn from_properties(adapter: Adapter, properties: &Properties) -> Result<Self> {
+        Ok(Self {
+            adapter,
+            name: property(properties, "Name")?,
+            model: optional_property(properties, "Model")?,
+            vendor: optional_property(properties, "Vendor")?,
+            supported_modes: property(properties, "SupportedModes")?,
+            powered: property(properties, "Powered")?,
+        })
     }
 }
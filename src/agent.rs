@@ -4,7 +4,7 @@ use strum::EnumString;
 use zbus::{Connection, Proxy, interface};
 use zvariant::OwnedObjectPath;
 
-use crate::{error::agent::Canceled, iwd_interface::IwdInterface, network::Network};
+use crate::{Error, error::agent::Canceled, iwd_interface::IwdInterface, network::Network};
 
 // AgentManager
 
@@ -32,11 +32,8 @@ impl AgentManager {
         .await
     }
 
-    pub(crate) async fn register_agent(&self, agent: impl Agent) -> zbus::Result<()> {
-        let proxy = self.proxy().await?;
-        proxy
-            .call_method("RegisterAgent", &(self.dbus_path))
-            .await?;
+    pub(crate) async fn register_agent(&self, agent: impl Agent) -> crate::Result<()> {
+        self.register().await?;
 
         let interface = AgentInterface {
             agent,
@@ -50,9 +47,33 @@ impl AgentManager {
 
         Ok(())
     }
+
+    /// Unregister the agent from iwd and stop serving it, `A` being the type it was registered with.
+    pub(crate) async fn unregister_agent<A: Agent>(&self) -> crate::Result<()> {
+        let proxy = self.proxy().await?;
+        proxy
+            .call_method("UnregisterAgent", &(self.dbus_path))
+            .await?;
+
+        self.connection
+            .object_server()
+            .remove::<AgentInterface<A>, _>(self.dbus_path.clone())
+            .await?;
+        Ok(())
+    }
+
+    /// Register the already served agent object with iwd.
+    pub(crate) async fn register(&self) -> crate::Result<()> {
+        let proxy = self.proxy().await?;
+        proxy
+            .call_method("RegisterAgent", &(self.dbus_path))
+            .await?;
+        Ok(())
+    }
 }
 
-#[derive(Debug, EnumString)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumString)]
+#[non_exhaustive]
 pub enum CancellationReason {
     #[strum(serialize = "out-of-range")]
     OutOfRange,
@@ -62,6 +83,9 @@ pub enum CancellationReason {
     Timeout,
     #[strum(serialize = "shutdown")]
     Shutdown,
+    /// A reason unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
 }
 
 pub trait Agent: Send + Sync + 'static {
@@ -116,7 +140,9 @@ impl<A: Agent> AgentInterface<A> {
 
     #[zbus(name = "RequestPassphrase")]
     async fn request_passphrase(&self, network_path: OwnedObjectPath) -> zbus::fdo::Result<String> {
-        let network = Network::new(self.connection.clone(), network_path).await?;
+        let network = Network::new(self.connection.clone(), network_path)
+            .await
+            .map_err(Error::into_fdo)?;
 
         Ok(self.agent.request_passphrase(&network).await?)
     }
@@ -126,7 +152,9 @@ impl<A: Agent> AgentInterface<A> {
         &self,
         network_path: OwnedObjectPath,
     ) -> zbus::fdo::Result<String> {
-        let network = Network::new(self.connection.clone(), network_path).await?;
+        let network = Network::new(self.connection.clone(), network_path)
+            .await
+            .map_err(Error::into_fdo)?;
         Ok(self.agent.request_private_key_passphrase(&network).await?)
     }
 
@@ -135,7 +163,9 @@ impl<A: Agent> AgentInterface<A> {
         &self,
         network_path: OwnedObjectPath,
     ) -> zbus::fdo::Result<(String, String)> {
-        let network = Network::new(self.connection.clone(), network_path).await?;
+        let network = Network::new(self.connection.clone(), network_path)
+            .await
+            .map_err(Error::into_fdo)?;
         Ok(self
             .agent
             .request_user_name_and_passphrase(&network)
@@ -148,7 +178,9 @@ impl<A: Agent> AgentInterface<A> {
         network_path: OwnedObjectPath,
         user_name: zvariant::Optional<String>,
     ) -> zbus::fdo::Result<String> {
-        let network = Network::new(self.connection.clone(), network_path).await?;
+        let network = Network::new(self.connection.clone(), network_path)
+            .await
+            .map_err(Error::into_fdo)?;
         let user_name = user_name.as_ref();
         Ok(self
             .agent
@@ -158,7 +190,8 @@ impl<A: Agent> AgentInterface<A> {
 
     #[zbus(name = "Cancel")]
     fn cancel(&self, reason: String) {
-        let reason = CancellationReason::from_str(&reason).unwrap();
+        let reason =
+            CancellationReason::from_str(&reason).unwrap_or(CancellationReason::Unknown(reason));
         self.agent.cancel(reason);
     }
 }
@@ -0,0 +1,318 @@
+//! Power every adapter and device off, then back to exactly the state they were in.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use async_broadcast::{InactiveReceiver, Sender};
+use futures_lite::{Stream, StreamExt, stream};
+
+use zvariant::OwnedObjectPath;
+
+use crate::{
+    Error, device::Device, error::airplane_mode::AirplaneModeError, session::Session, timer,
+};
+
+// Only the latest status matters to slow subscribers.
+const CHANNEL_CAPACITY: usize = 4;
+// iwd brings the devices of an adapter back up some time after powering the adapter on, recreating them with some
+// drivers.
+const DEVICE_SETTLE_TIMEOUT: Duration = Duration::from_secs(3);
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Airplane mode for all the adapters and devices of iwd.
+///
+/// While enabled, the powered state of every adapter and device is saved to a file, so that it survives a reboot
+/// or a restart of iwd. The state is keyed by adapter and device name, as object paths change across restarts.
+#[derive(Debug, Clone)]
+pub struct AirplaneMode {
+    session: Session,
+    state_path: PathBuf,
+    sender: Sender<bool>,
+    receiver: InactiveReceiver<bool>,
+}
+
+/// The powered state recorded when airplane mode got enabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SavedState {
+    adapters: Vec<(String, bool)>,
+    devices: Vec<(String, bool)>,
+}
+
+impl AirplaneMode {
+    /// `state_path` is the file holding the saved state, its existence meaning that airplane mode is enabled.
+    pub fn new(session: Session, state_path: impl Into<PathBuf>) -> Self {
+        let (mut sender, receiver) = async_broadcast::broadcast(CHANNEL_CAPACITY);
+        sender.set_overflow(true);
+
+        Self {
+            session,
+            state_path: state_path.into(),
+            sender,
+            receiver: receiver.deactivate(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state_path.exists()
+    }
+
+    /// The current status, then its changes made through this handle or its clones. The state file is not watched,
+    /// so changes made by other handles or processes only show up in [`AirplaneMode::is_enabled`].
+    pub fn status_stream(&self) -> impl Stream<Item = bool> + Unpin + Send + 'static {
+        Box::pin(stream::once(self.is_enabled()).chain(self.receiver.activate_cloned()))
+    }
+
+    /// Save the powered state of every adapter and device, then power them all off. Does nothing if airplane mode
+    /// is already enabled.
+    ///
+    /// The state is saved before powering anything off, so that [`AirplaneMode::disable`] restores it even if this
+    /// fails half way.
+    pub async fn enable(&self) -> crate::Result<(), AirplaneModeError> {
+        if self.is_enabled() {
+            return Ok(());
+        }
+
+        let adapters = self
+            .session
+            .adapters_info()
+            .await
+            .map_err(Error::into_operation)?;
+        let devices = self
+            .session
+            .devices_info()
+            .await
+            .map_err(Error::into_operation)?;
+        let state = SavedState {
+            adapters: adapters
+                .iter()
+                .map(|adapter| (adapter.name.clone(), adapter.powered))
+                .collect(),
+            devices: devices
+                .iter()
+                .map(|device| (device.name.clone(), device.powered))
+                .collect(),
+        };
+        state
+            .write(&self.state_path)
+            .map_err(|err| Error::Io(err).into_operation())?;
+        let _ = self.sender.try_broadcast(true);
+
+        self.power_off().await
+    }
+
+    /// Power everything off again while airplane mode is enabled, keeping the saved state. iwd powers adapters on
+    /// when it starts, so this is meant to be called after a reboot or a restart of iwd.
+    pub async fn reapply(&self) -> crate::Result<(), AirplaneModeError> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        self.power_off().await
+    }
+
+    /// Power on the adapters and devices which were powered when airplane mode got enabled, and forget the saved
+    /// state. Does nothing if airplane mode is not enabled.
+    ///
+    /// Adapters and devices which no longer exist are skipped. The devices of the adapters which get powered on are
+    /// given a few seconds to come back up before their saved state is applied.
+    pub async fn disable(&self) -> crate::Result<(), AirplaneModeError> {
+        let state = match SavedState::read(&self.state_path) {
+            Ok(state) => state,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(Error::Io(err).into_operation()),
+        };
+
+        let mut powered_adapters = Vec::new();
+        for adapter in self
+            .session
+            .adapters_info()
+            .await
+            .map_err(Error::into_operation)?
+        {
+            if state.adapters.contains(&(adapter.name, true)) && !adapter.powered {
+                adapter
+                    .adapter
+                    .set_power(true)
+                    .await
+                    .map_err(|err| err.map_operation(AirplaneModeError::Adapter))?;
+                powered_adapters.push(adapter.adapter.object_path());
+            }
+        }
+
+        // Devices come back powered along with their adapter, the ones which were off get powered off again. The
+        // saved state is applied even when it already matches, as iwd may not be done bringing the device up.
+        let deadline = Instant::now() + DEVICE_SETTLE_TIMEOUT;
+        for (name, powered) in &state.devices {
+            let Some(device) = self
+                .settled_device(name, &powered_adapters, deadline)
+                .await
+                .map_err(Error::into_operation)?
+            else {
+                continue;
+            };
+            device
+                .set_power(*powered)
+                .await
+                .map_err(|err| err.map_operation(AirplaneModeError::Device))?;
+        }
+
+        fs::remove_file(&self.state_path).map_err(|err| Error::Io(err).into_operation())?;
+        let _ = self.sender.try_broadcast(false);
+        Ok(())
+    }
+
+    /// The device named `name`, once it is powered if its adapter is one of `powered_adapters`. Gives up waiting at
+    /// `deadline`, and returns `None` if the device does not exist by then.
+    async fn settled_device(
+        &self,
+        name: &str,
+        powered_adapters: &[OwnedObjectPath],
+        deadline: Instant,
+    ) -> crate::Result<Option<Device>> {
+        loop {
+            let device = self
+                .session
+                .devices_info()
+                .await?
+                .into_iter()
+                .find(|device| device.name == name);
+            match device {
+                Some(device) if device.powered || !powered_adapters.contains(&device.adapter) => {
+                    return Ok(Some(device.device));
+                }
+                None if powered_adapters.is_empty() => return Ok(None),
+                device if Instant::now() >= deadline => {
+                    return Ok(device.map(|device| device.device));
+                }
+                _ => timer::sleep(DEVICE_POLL_INTERVAL).await,
+            }
+        }
+    }
+
+    async fn power_off(&self) -> crate::Result<(), AirplaneModeError> {
+        for device in self
+            .session
+            .devices_info()
+            .await
+            .map_err(Error::into_operation)?
+        {
+            if device.powered {
+                device
+                    .device
+                    .set_power(false)
+                    .await
+                    .map_err(|err| err.map_operation(AirplaneModeError::Device))?;
+            }
+        }
+
+        for adapter in self
+            .session
+            .adapters_info()
+            .await
+            .map_err(Error::into_operation)?
+        {
+            if adapter.powered {
+                adapter
+                    .adapter
+                    .set_power(false)
+                    .await
+                    .map_err(|err| err.map_operation(AirplaneModeError::Adapter))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SavedState {
+    /// One `adapter <name> <powered>` or `device <name> <powered>` line per object. Fails with
+    /// [`io::ErrorKind::InvalidData`] on any other line, so that a corrupted file is kept rather than restoring
+    /// nothing.
+    fn read(path: &Path) -> io::Result<Self> {
+        let mut state = Self::default();
+        for line in fs::read_to_string(path)?.lines() {
+            let invalid = || {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected line in {}: {line}", path.display()),
+                )
+            };
+
+            let mut fields = line.split_whitespace();
+            let (Some(kind), Some(name), Some(powered), None) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid());
+            };
+            let powered = powered.parse().map_err(|_| invalid())?;
+            match kind {
+                "adapter" => state.adapters.push((name.to_string(), powered)),
+                "device" => state.devices.push((name.to_string(), powered)),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(state)
+    }
+
+    /// Written to a temporary file first, so that a crash never leaves a truncated state behind.
+    fn write(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        let mut file = fs::File::create(&temporary_path)?;
+        for (name, powered) in &self.adapters {
+            writeln!(file, "adapter {name} {powered}")?;
+        }
+        for (name, powered) in &self.devices {
+            writeln!(file, "device {name} {powered}")?;
+        }
+        file.sync_all()?;
+
+        fs::rename(temporary_path, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io, path::PathBuf};
+
+    use super::SavedState;
+
+    fn state_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("iwdrs-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn round_trips_the_saved_state() {
+        let path = state_path("round-trip");
+        let state = SavedState {
+            adapters: vec![("phy0".to_string(), true)],
+            devices: vec![("wlan0".to_string(), false), ("wlan1".to_string(), true)],
+        };
+
+        state.write(&path).unwrap();
+        let read = SavedState::read(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), state);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        let path = state_path("malformed");
+        for content in [
+            "adapter phy0 true\ndevice wlan0\n",
+            "adapter phy0 yes\n",
+            "station wlan0 true\n",
+        ] {
+            fs::write(&path, content).unwrap();
+            let read = SavedState::read(&path);
+            assert_eq!(read.unwrap_err().kind(), io::ErrorKind::InvalidData);
+        }
+        fs::remove_file(&path).unwrap();
+    }
+}
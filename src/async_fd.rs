@@ -0,0 +1,50 @@
+//! Reads from nonblocking file descriptors, such as `/dev/rfkill` or a netlink socket, on the runtime the crate is
+//! built for.
+
+use std::{
+    io,
+    os::fd::{AsFd, AsRawFd},
+};
+
+/// A nonblocking file descriptor registered with the reactor of the runtime.
+#[cfg(feature = "tokio")]
+pub(crate) struct AsyncFd<T: AsFd + AsRawFd>(tokio::io::unix::AsyncFd<T>);
+
+#[cfg(feature = "tokio")]
+impl<T: AsFd + AsRawFd> AsyncFd<T> {
+    pub(crate) fn new(inner: T) -> io::Result<Self> {
+        tokio::io::unix::AsyncFd::new(inner).map(Self)
+    }
+
+    /// Run `read` once the descriptor is readable, and again each time it fails with `WouldBlock`.
+    pub(crate) async fn read_with<R>(
+        &self,
+        mut read: impl FnMut(&T) -> io::Result<R>,
+    ) -> io::Result<R> {
+        loop {
+            let mut guard = self.0.readable().await?;
+            if let Ok(result) = guard.try_io(|inner| read(inner.get_ref())) {
+                return result;
+            }
+        }
+    }
+}
+
+/// A nonblocking file descriptor registered with the reactor of the runtime.
+#[cfg(not(feature = "tokio"))]
+pub(crate) struct AsyncFd<T: AsFd + AsRawFd>(async_io::Async<T>);
+
+#[cfg(not(feature = "tokio"))]
+impl<T: AsFd + AsRawFd> AsyncFd<T> {
+    pub(crate) fn new(inner: T) -> io::Result<Self> {
+        async_io::Async::new(inner).map(Self)
+    }
+
+    /// Run `read` once the descriptor is readable, and again each time it fails with `WouldBlock`.
+    pub(crate) async fn read_with<R>(
+        &self,
+        read: impl FnMut(&T) -> io::Result<R>,
+    ) -> io::Result<R> {
+        self.0.read_with(read).await
+    }
+}
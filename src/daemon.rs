@@ -1,14 +1,14 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use crate::iwd_interface::iwd_interface_impl;
-use zvariant::{OwnedObjectPath, Value};
+use zvariant::Value;
 
-use zbus::{Connection, Proxy};
+use zbus::Proxy;
 
 iwd_interface_impl!(Daemon, "net.connman.iwd.Daemon");
 
 impl Daemon {
-    pub async fn get_info(&self) -> zbus::Result<DaemonInfo> {
+    pub async fn get_info(&self) -> crate::Result<DaemonInfo> {
         let info = self.proxy.call_method("GetInfo", &()).await?;
         let info = info.body();
         let info: HashMap<String, Value> = info.deserialize()?;
@@ -24,11 +24,17 @@ pub struct DaemonInfo {
 }
 
 impl DaemonInfo {
-    pub(crate) fn from_zbus_map(body: HashMap<String, Value>) -> zbus::Result<Self> {
-        let state_dir: zvariant::Str = body.get("StateDirectory").unwrap().try_into()?;
+    pub(crate) fn from_zbus_map(body: HashMap<String, Value>) -> crate::Result<Self> {
+        let state_dir: zvariant::Str = body
+            .get("StateDirectory")
+            .ok_or_else(|| crate::Error::missing_property("StateDirectory"))?
+            .try_into()?;
         let state_dir: PathBuf = PathBuf::from(state_dir.as_str());
 
-        let version: zvariant::Str = body.get("Version").unwrap().try_into()?;
+        let version: zvariant::Str = body
+            .get("Version")
+            .ok_or_else(|| crate::Error::missing_property("Version"))?
+            .try_into()?;
         let version = version.to_string();
 
         Ok(Self {
@@ -36,7 +42,7 @@ impl DaemonInfo {
             version,
             network_config_enabled: body
                 .get("NetworkConfigurationEnabled")
-                .unwrap()
+                .ok_or_else(|| crate::Error::missing_property("NetworkConfigurationEnabled"))?
                 .try_into()?,
         })
     }
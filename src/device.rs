@@ -1,51 +1,163 @@
-use zbus::{Connection, Proxy, Result};
+use std::{str::FromStr, time::Duration};
+
+use futures_lite::{StreamExt, future};
+use zbus::Proxy;
 use zvariant::OwnedObjectPath;
 
 use crate::{
+    Error, Result,
+    access_point::AccessPoint,
     adapter::Adapter,
-    iwd_interface::{IwdInterface, iwd_interface_impl},
+    error::device::{SetModeError, SetPowerError},
+    iwd_interface::{IwdInterface, Properties, get_all, iwd_interface_impl, property},
+    link::{self, AddressMonitor, InterfaceAddress, LinkInfo},
+    mac_address::MacAddress,
     modes::Mode,
+    session::{interfaces_added, wait_for_interface},
+    station::{State, Station},
+    timer, unblock,
 };
 
-iwd_interface_impl!(Device, "net.connman.iwd.Device");
+// Switching modes makes iwd tear down and recreate the interfaces of the device.
+const MODE_SWITCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+iwd_interface_impl!(Device, "net.connman.iwd.Device", {
+    Name: String => name, name_stream;
+    Address: MacAddress => address, address_stream;
+    Adapter: OwnedObjectPath => adapter_path, adapter_path_stream;
+    Mode: Mode => get_mode, mode_stream, set_mode -> SetModeError;
+    Powered: bool => is_powered, powered_stream, set_power -> SetPowerError;
+});
 
 impl Device {
-    pub async fn name(&self) -> Result<String> {
-        self.proxy.get_property("Name").await
+    pub async fn adapter(&self) -> Result<Adapter> {
+        let adapter =
+            Adapter::new(self.proxy.connection().clone(), self.adapter_path().await?).await?;
+        Ok(adapter)
     }
 
-    pub async fn address(&self) -> Result<String> {
-        self.proxy.get_property("Address").await
+    /// The address burnt into the hardware, read from sysfs. It differs from [`Device::address`] while iwd uses a
+    /// randomized address.
+    pub async fn permanent_address(&self) -> Result<MacAddress> {
+        let path = format!("/sys/class/net/{}/phy80211/macaddress", self.name().await?);
+        let address = unblock::unblock(move || std::fs::read_to_string(path)).await?;
+        MacAddress::from_str(address.trim()).map_err(|err| Error::TypeMismatch(err.to_string()))
     }
 
-    pub async fn adapter(&self) -> Result<Adapter> {
-        let adapter_path: OwnedObjectPath = self.proxy.get_property("Adapter").await?;
-        let adapter = Adapter::new(self.proxy.connection().clone(), adapter_path).await?;
-        Ok(adapter)
+    /// Whether the current address of the device is not its permanent one, i.e. whether address randomization is in
+    /// effect.
+    pub async fn has_randomized_address(&self) -> Result<bool> {
+        let (address, permanent_address) =
+            future::try_zip(self.address(), self.permanent_address()).await?;
+        Ok(address != permanent_address)
     }
 
-    pub async fn get_mode(&self) -> Result<Mode> {
-        let mode: String = self.proxy.get_property("Mode").await?;
+    /// Kernel state of the network interface of the device: driver, phy, operational state and addresses.
+    pub async fn link(&self) -> Result<LinkInfo> {
+        let name = self.name().await?;
+        unblock::unblock(move || LinkInfo::for_interface(&name)).await
+    }
 
-        match mode.as_str() {
-            "station" => Ok(Mode::Station),
-            "ap" => Ok(Mode::Ap),
-            _ => unimplemented!(),
-        }
+    /// Wait until the station of the device is connected and its network interface has a usable address which is
+    /// not link-local, e.g. once DHCP completes. Fails with [`Error::Timeout`] if none shows up within `timeout`.
+    ///
+    /// Addresses still waiting for IPv6 duplicate address detection, and deprecated ones, are skipped. An address
+    /// left on the interface by a previous connection is returned if it is still there once the station is
+    /// connected, as the kernel does not tell it apart from a renewed one.
+    pub async fn wait_for_address(&self, timeout: Duration) -> Result<InterfaceAddress> {
+        let name = self.name().await?;
+        let station = Station::new(self.proxy.connection().clone(), self.object_path()).await?;
+        let address = async {
+            let mut states = station.state_stream().await?;
+            while states.next().await.ok_or(Error::ObjectVanished)?? != State::Connected {}
+
+            let ifindex = link::ifindex(&name)?;
+            let monitor = AddressMonitor::open()?;
+            loop {
+                if let Some(message) = monitor.next().await?.into_iter().find(|message| {
+                    message.ifindex == ifindex
+                        && message.is_usable()
+                        && !message.address.is_link_local()
+                }) {
+                    return Ok(message.address);
+                }
+            }
+        };
+        timer::timeout(timeout, address)
+            .await
+            .unwrap_or(Err(Error::Timeout))
     }
 
-    pub async fn is_powered(&self) -> Result<bool> {
-        let is_powered: bool = self.proxy.get_property("Powered").await?;
-        Ok(is_powered)
+    /// Switch the device to station mode and return its [`Station`] once iwd publishes it. Fails with
+    /// [`SetModeError::NotSupported`] if the adapter does not support station mode, and with [`Error::Timeout`] if
+    /// the station does not appear within 5 seconds.
+    pub async fn switch_to_station(&self) -> Result<Station, SetModeError> {
+        self.switch_mode(Mode::Station).await
     }
 
-    pub async fn set_mode(&self, mode: Mode) -> Result<()> {
-        self.proxy.set_property("Mode", mode.to_string()).await?;
-        Ok(())
+    /// Switch the device to access point mode and return its [`AccessPoint`] once iwd publishes it. Fails with
+    /// [`SetModeError::NotSupported`] if the adapter does not support access point mode, and with
+    /// [`Error::Timeout`] if the access point does not appear within 5 seconds.
+    pub async fn switch_to_ap(&self) -> Result<AccessPoint, SetModeError> {
+        self.switch_mode(Mode::Ap).await
     }
 
-    pub async fn set_power(&self, mode: bool) -> Result<()> {
-        self.proxy.set_property("Powered", mode).await?;
-        Ok(())
+    async fn switch_mode<Object: IwdInterface>(&self, mode: Mode) -> Result<Object, SetModeError> {
+        let supported_modes = self
+            .adapter()
+            .await
+            .map_err(Error::into_operation)?
+            .supported_modes()
+            .await
+            .map_err(Error::into_operation)?;
+        if !supported_modes.contains(&mode.to_string()) {
+            return Err(Error::Operation {
+                error: SetModeError::NotSupported,
+                description: Some(format!("the adapter does not support the {mode} mode")),
+            });
+        }
+
+        // Subscribe before switching, iwd publishes the new interface right after replying.
+        let connection = self.proxy.connection();
+        let added = interfaces_added(connection)
+            .await
+            .map_err(Error::into_operation)?;
+
+        if self.get_mode().await.map_err(Error::into_operation)? != mode {
+            self.set_mode(mode).await?;
+        }
+
+        wait_for_interface(connection, added, &self.object_path(), MODE_SWITCH_TIMEOUT)
+            .await
+            .map_err(Error::into_operation)
+    }
+
+    /// Fetch all the properties of the device in a single call.
+    pub async fn info(&self) -> Result<DeviceInfo> {
+        DeviceInfo::from_properties(self.clone(), &get_all(&self.proxy).await?)
+    }
+}
+
+/// Snapshot of the properties of a [`Device`].
+#[derive(Debug, Clone)]
+pub struct DeviceInfo {
+    pub device: Device,
+    pub name: String,
+    pub address: MacAddress,
+    pub adapter: OwnedObjectPath,
+    pub mode: Mode,
+    pub powered: bool,
+}
+
+impl DeviceInfo {
+    pub(crate) fn from_properties(device: Device, properties: &Properties) -> Result<Self> {
+        Ok(Self {
+            device,
+            name: property(properties, "Name")?,
+            address: property(properties, "Address")?,
+            adapter: property(properties, "Adapter")?,
+            mode: property(properties, "Mode")?,
+            powered: property(properties, "Powered")?,
+        })
     }
 }
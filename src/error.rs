@@ -1,39 +1,201 @@
-use strum::EnumMessage;
+use std::{fmt::Display, str::FromStr};
+
 use thiserror::Error;
+use zbus::{DBusError, fdo};
 
 pub mod access_point;
+pub mod adapter;
 pub mod agent;
+pub mod airplane_mode;
+pub mod device;
+pub mod hotspot;
+pub mod known_network;
 pub mod network;
 pub mod station;
 
-pub type Result<T, E> = std::result::Result<T, IWDError<E>>;
+pub type Result<T, E = NoOperationError> = std::result::Result<T, Error<E>>;
+
+#[deprecated(note = "use `iwdrs::Error` instead")]
+pub type IWDError<E> = Error<E>;
+
+/// Errors of the crate, `E` being the errors documented by iwd for the operation that failed.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error<E = NoOperationError> {
+    /// iwd rejected the operation with one of its documented errors.
+    #[error("iwd operation failed: {error}")]
+    Operation {
+        error: E,
+        /// The description sent by iwd along with the error.
+        description: Option<String>,
+    },
+    /// iwd replied with an error not documented for the operation.
+    #[error("iwd error {name}: {}", description.as_deref().unwrap_or("no description"))]
+    Iwd {
+        name: String,
+        description: Option<String>,
+    },
+    /// iwd is not running, or left the bus during the call.
+    #[error("iwd is not running")]
+    DaemonNotRunning,
+    /// The object does not exist anymore on iwd's side, e.g. a network that went out of range.
+    #[error("the iwd object does not exist anymore")]
+    ObjectVanished,
+    /// iwd sent a value this crate does not know how to decode.
+    #[error("unexpected value received from iwd: {0}")]
+    TypeMismatch(String),
+    /// iwd did not reach the expected state or did not reply in time.
+    #[error("timed out waiting for iwd")]
+    Timeout,
+    /// An argument was rejected before reaching iwd.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+    /// Reading or writing a file outside of iwd failed, e.g. in sysfs.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Communication with iwd over D-Bus failed.
+    #[error("D-Bus transport error: {0}")]
+    Transport(#[source] TransportError),
+}
 
+/// A D-Bus failure which is not specific to iwd, e.g. the system bus being unreachable.
 #[derive(Debug, Error)]
-pub enum IWDError<T: std::str::FromStr + std::error::Error> {
-    #[error("IWD Error: {0}")]
-    OperationError(T),
-    #[error("Dbus error when executing IWD operation: {0}")]
-    ZbusError(zbus::Error),
-}
-
-impl<T: std::str::FromStr<Err = strum::ParseError> + std::error::Error + EnumMessage>
-    From<zbus::Error> for IWDError<T>
-{
-    fn from(value: zbus::Error) -> Self {
-        let zbus::Error::MethodError(error_name, _error_description, _) = &value else {
-            return Self::ZbusError(value);
-        };
-        match T::from_str(error_name.as_str()) {
-            Ok(error) => {
-                debug_assert!(
-                    _error_description
-                        .as_ref()
-                        .is_some_and(|err| err.as_str() == error.get_detailed_message().unwrap())
-                );
-
-                Self::OperationError(error)
+#[error(transparent)]
+pub struct TransportError(zbus::Error);
+
+/// Error type of the operations for which iwd does not document any error, it has no value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoOperationError {}
+
+impl Display for NoOperationError {
+    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {}
+    }
+}
+
+impl std::error::Error for NoOperationError {}
+
+impl FromStr for NoOperationError {
+    type Err = strum::ParseError;
+
+    fn from_str(_s: &str) -> std::result::Result<Self, Self::Err> {
+        Err(strum::ParseError::VariantNotFound)
+    }
+}
+
+impl<E: FromStr> Error<E> {
+    fn from_error_name(name: &str, description: Option<String>) -> Self {
+        match name {
+            "org.freedesktop.DBus.Error.ServiceUnknown"
+            | "org.freedesktop.DBus.Error.NameHasNoOwner" => return Self::DaemonNotRunning,
+            // iwd may still be running, e.g. busy with a slow driver.
+            "org.freedesktop.DBus.Error.NoReply" => return Self::Timeout,
+            "org.freedesktop.DBus.Error.UnknownObject"
+            | "org.freedesktop.DBus.Error.UnknownInterface" => return Self::ObjectVanished,
+            _ => {}
+        }
+
+        match E::from_str(name) {
+            Ok(error) => Self::Operation { error, description },
+            Err(_) => Self::Iwd {
+                name: name.to_string(),
+                description,
+            },
+        }
+    }
+}
+
+impl<E> Error<E> {
+    /// Convert the operation error, e.g. to wrap it in the error of a higher level operation.
+    pub(crate) fn map_operation<F>(self, f: impl FnOnce(E) -> F) -> Error<F> {
+        match self {
+            Self::Operation { error, description } => Error::Operation {
+                error: f(error),
+                description,
+            },
+            Self::Iwd { name, description } => Error::Iwd { name, description },
+            Self::DaemonNotRunning => Error::DaemonNotRunning,
+            Self::ObjectVanished => Error::ObjectVanished,
+            Self::TypeMismatch(message) => Error::TypeMismatch(message),
+            Self::InvalidArgument(message) => Error::InvalidArgument(message),
+            Self::Timeout => Error::Timeout,
+            Self::Io(err) => Error::Io(err),
+            Self::Transport(err) => Error::Transport(err),
+        }
+    }
+
+    pub(crate) fn missing_property(name: &str) -> Self {
+        Self::TypeMismatch(format!("missing property {name}"))
+    }
+
+    /// Reply sent back to iwd when a call made by iwd on one of our objects fails.
+    pub(crate) fn into_fdo(self) -> fdo::Error
+    where
+        E: Display,
+    {
+        fdo::Error::Failed(self.to_string())
+    }
+}
+
+impl<E: FromStr> From<zbus::Error> for Error<E> {
+    fn from(error: zbus::Error) -> Self {
+        match error {
+            zbus::Error::MethodError(name, description, _) => {
+                Self::from_error_name(name.as_str(), description)
             }
-            Err(_) => Self::ZbusError(value),
+            zbus::Error::FDO(error) => Self::from(*error),
+            zbus::Error::Variant(error) => Self::TypeMismatch(error.to_string()),
+            zbus::Error::InvalidReply => Self::TypeMismatch("invalid reply".to_string()),
+            error => Self::Transport(TransportError(error)),
+        }
+    }
+}
+
+impl<E: FromStr> From<fdo::Error> for Error<E> {
+    fn from(error: fdo::Error) -> Self {
+        match error {
+            fdo::Error::ZBus(error) => Self::from(error),
+            error => Self::from_error_name(
+                error.name().as_str(),
+                error.description().map(str::to_string),
+            ),
         }
     }
 }
+
+impl Error {
+    /// The same error, as an error of an operation which documents errors.
+    pub(crate) fn into_operation<E>(self) -> Error<E> {
+        self.map_operation(|error| match error {})
+    }
+}
+
+impl<E> From<zvariant::Error> for Error<E> {
+    fn from(error: zvariant::Error) -> Self {
+        Self::TypeMismatch(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_bus_errors_to_their_cause() {
+        let error = |name| Error::<NoOperationError>::from_error_name(name, None);
+
+        assert!(matches!(
+            error("org.freedesktop.DBus.Error.ServiceUnknown"),
+            Error::DaemonNotRunning
+        ));
+        assert!(matches!(
+            error("org.freedesktop.DBus.Error.NoReply"),
+            Error::Timeout
+        ));
+        assert!(matches!(
+            error("org.freedesktop.DBus.Error.UnknownObject"),
+            Error::ObjectVanished
+        ));
+        assert!(matches!(error("net.connman.iwd.Failed"), Error::Iwd { .. }));
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt::Display;
+
+use strum::{EnumMessage, EnumString};
+use thiserror::Error;
+
+#[derive(Debug, EnumString, EnumMessage, Error)]
+pub enum SetPowerError {
+    #[strum(
+        serialize = "net.connman.iwd.Busy",
+        serialize = "net.connman.iwd.InProgress",
+        message = "InProgress",
+        detailed_message = "Operation already in progress"
+    )]
+    Busy,
+    #[strum(
+        serialize = "net.connman.iwd.Failed",
+        serialize = "org.freedesktop.DBus.Error.Failed",
+        message = "Failed",
+        detailed_message = "Operation failed"
+    )]
+    Failed,
+    /// The adapter is hard blocked by rfkill.
+    #[strum(
+        serialize = "net.connman.iwd.NotAvailable",
+        message = "NotAvailable",
+        detailed_message = "Adapter is hard blocked"
+    )]
+    NotAvailable,
+    #[strum(
+        serialize = "net.connman.iwd.InvalidArguments",
+        serialize = "org.freedesktop.DBus.Error.InvalidArgs",
+        message = "InvalidArguments",
+        detailed_message = "Argument type is wrong"
+    )]
+    InvalidArguments,
+    /// Reported by [`crate::adapter::Adapter::power_on`], the radio is blocked by a hardware switch.
+    #[strum(
+        message = "HardBlocked",
+        detailed_message = "Radio blocked by a hardware switch"
+    )]
+    HardBlocked,
+    /// Reported by [`crate::adapter::Adapter::power_on`] when it is not allowed to clear the soft block.
+    #[strum(
+        message = "SoftBlocked",
+        detailed_message = "Radio blocked by software"
+    )]
+    SoftBlocked,
+}
+
+impl Display for SetPowerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_detailed_message().unwrap())
+    }
+}
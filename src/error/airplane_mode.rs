@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+use crate::error::{adapter, device};
+
+/// Errors of the power changes made by [`crate::airplane_mode::AirplaneMode`].
+#[derive(Debug, Error)]
+pub enum AirplaneModeError {
+    #[error(transparent)]
+    Adapter(adapter::SetPowerError),
+    #[error(transparent)]
+    Device(device::SetPowerError),
+}
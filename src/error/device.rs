@@ -0,0 +1,88 @@
+use std::fmt::Display;
+
+use strum::{EnumMessage, EnumString};
+use thiserror::Error;
+
+#[derive(Debug, EnumString, EnumMessage, Error)]
+pub enum SetPowerError {
+    #[strum(
+        serialize = "net.connman.iwd.Busy",
+        serialize = "net.connman.iwd.InProgress",
+        message = "InProgress",
+        detailed_message = "Operation already in progress"
+    )]
+    Busy,
+    #[strum(
+        serialize = "net.connman.iwd.Failed",
+        serialize = "org.freedesktop.DBus.Error.Failed",
+        message = "Failed",
+        detailed_message = "Operation failed"
+    )]
+    Failed,
+    /// The adapter of the device is powered off or blocked by rfkill.
+    #[strum(
+        serialize = "net.connman.iwd.NotAvailable",
+        message = "NotAvailable",
+        detailed_message = "Operation not available"
+    )]
+    NotAvailable,
+    #[strum(
+        serialize = "net.connman.iwd.InvalidArguments",
+        serialize = "org.freedesktop.DBus.Error.InvalidArgs",
+        message = "InvalidArguments",
+        detailed_message = "Argument type is wrong"
+    )]
+    InvalidArguments,
+}
+
+impl Display for SetPowerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_detailed_message().unwrap())
+    }
+}
+
+#[derive(Debug, EnumString, EnumMessage, Error)]
+pub enum SetModeError {
+    #[strum(
+        serialize = "net.connman.iwd.Busy",
+        serialize = "net.connman.iwd.InProgress",
+        message = "InProgress",
+        detailed_message = "Operation already in progress"
+    )]
+    Busy,
+    #[strum(
+        serialize = "net.connman.iwd.Failed",
+        serialize = "org.freedesktop.DBus.Error.Failed",
+        message = "Failed",
+        detailed_message = "Operation failed"
+    )]
+    Failed,
+    /// The device is powered off.
+    #[strum(
+        serialize = "net.connman.iwd.NotAvailable",
+        message = "NotAvailable",
+        detailed_message = "Operation not available"
+    )]
+    NotAvailable,
+    /// The hardware does not support the requested mode.
+    #[strum(
+        serialize = "net.connman.iwd.NotSupported",
+        serialize = "org.freedesktop.DBus.Error.NotSupported",
+        message = "NotSupported",
+        detailed_message = "Operation not supported"
+    )]
+    NotSupported,
+    #[strum(
+        serialize = "net.connman.iwd.InvalidArguments",
+        serialize = "org.freedesktop.DBus.Error.InvalidArgs",
+        message = "InvalidArguments",
+        detailed_message = "Argument type is wrong"
+    )]
+    InvalidArguments,
+}
+
+impl Display for SetModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_detailed_message().unwrap())
+    }
+}
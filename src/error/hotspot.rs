@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+use crate::error::{
+    access_point::{AccessPointStartError, AccessPointStopError, StartProfileError},
+    device::SetModeError,
+    network::ConnectError,
+};
+
+/// Errors of the operations making up [`crate::hotspot::Hotspot::start`] and [`crate::hotspot::Hotspot::stop`].
+#[derive(Debug, Error)]
+pub enum HotspotError {
+    #[error(transparent)]
+    SetMode(SetModeError),
+    #[error(transparent)]
+    Start(AccessPointStartError),
+    #[error(transparent)]
+    StartProfile(StartProfileError),
+    #[error(transparent)]
+    Stop(AccessPointStopError),
+    #[error(transparent)]
+    Reconnect(ConnectError),
+}
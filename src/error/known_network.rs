@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+use strum::{EnumMessage, EnumString};
+use thiserror::Error;
+
+#[derive(Debug, EnumString, EnumMessage, Error)]
+pub enum SetAutoConnectError {
+    #[strum(
+        serialize = "net.connman.iwd.Failed",
+        serialize = "org.freedesktop.DBus.Error.Failed",
+        message = "Failed",
+        detailed_message = "Operation failed"
+    )]
+    Failed,
+    #[strum(
+        serialize = "net.connman.iwd.InvalidArguments",
+        serialize = "org.freedesktop.DBus.Error.InvalidArgs",
+        message = "InvalidArguments",
+        detailed_message = "Argument type is wrong"
+    )]
+    InvalidArguments,
+}
+
+impl Display for SetAutoConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_detailed_message().unwrap())
+    }
+}
+
+#[derive(Debug, EnumString, EnumMessage, Error)]
+pub enum ForgetError {
+    #[strum(
+        serialize = "net.connman.iwd.Failed",
+        message = "Failed",
+        detailed_message = "Operation failed"
+    )]
+    Failed,
+    /// The network was already forgotten.
+    #[strum(
+        serialize = "net.connman.iwd.NotFound",
+        message = "NotFound",
+        detailed_message = "Object not found"
+    )]
+    NotFound,
+}
+
+impl Display for ForgetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_detailed_message().unwrap())
+    }
+}
@@ -1,8 +1,8 @@
-use crate::network::NetworkType;
+use crate::{mac_address::MacAddress, network::NetworkType, signal_strength::SignalStrength};
 
 #[derive(Debug, Clone)]
 pub struct HiddenNetwork {
-    pub address: String,
-    pub signal_strength: i16,
+    pub address: MacAddress,
+    pub signal_strength: SignalStrength,
     pub network_type: NetworkType,
 }
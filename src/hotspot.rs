@@ -0,0 +1,171 @@
+//! Turn a device into an access point and back into a station.
+
+use std::time::Duration;
+
+use crate::{
+    Error,
+    access_point::{AccessPoint, profile::ApProfile},
+    device::Device,
+    error::hotspot::HotspotError,
+    iwd_interface::IwdInterface,
+    modes::Mode,
+    network::NetworkId,
+    station::Station,
+    timer,
+};
+
+pub mod guest;
+
+const SCAN_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How the access point gets started by [`Hotspot::start`].
+#[derive(Debug, Clone)]
+pub enum HotspotConfig {
+    Passphrase { ssid: String, passphrase: String },
+    Profile(ApProfile),
+}
+
+/// An access point started by [`Hotspot::start`] on a device which was in station mode.
+///
+/// Dropping the handle leaves the access point running, call [`Hotspot::stop`] to get back to station mode.
+#[derive(Debug)]
+pub struct Hotspot {
+    device: Device,
+    access_point: AccessPoint,
+    previous_mode: Mode,
+    previous_network: Option<NetworkId>,
+}
+
+impl Hotspot {
+    /// Switch the device to access point mode and start the access point. On failure, the device is switched back
+    /// to its previous mode and reconnected to its previous network.
+    pub async fn start(device: Device, config: HotspotConfig) -> crate::Result<Self, HotspotError> {
+        let previous_mode = device.get_mode().await.map_err(Error::into_operation)?;
+        let previous_network = match previous_mode {
+            Mode::Station => connected_network_id(&device).await,
+            _ => None,
+        };
+
+        let access_point = device
+            .switch_to_ap()
+            .await
+            .map_err(|err| err.map_operation(HotspotError::SetMode));
+        let hotspot = match access_point {
+            Ok(access_point) => Self {
+                device,
+                access_point,
+                previous_mode,
+                previous_network,
+            },
+            Err(err) => {
+                let _ = restore(&device, &previous_mode, previous_network.as_ref()).await;
+                return Err(err);
+            }
+        };
+
+        let started = match &config {
+            HotspotConfig::Passphrase { ssid, passphrase } => hotspot
+                .access_point
+                .start(ssid, passphrase)
+                .await
+                .map_err(|err| err.map_operation(HotspotError::Start)),
+            HotspotConfig::Profile(profile) => hotspot
+                .access_point
+                .start_with_profile(profile)
+                .await
+                .map_err(|err| err.map_operation(HotspotError::StartProfile)),
+        };
+        if let Err(err) = started {
+            let _ = hotspot.restore().await;
+            return Err(err);
+        }
+
+        Ok(hotspot)
+    }
+
+    pub fn access_point(&self) -> &AccessPoint {
+        &self.access_point
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Stop the access point, switch the device back to its previous mode and reconnect to the network it was
+    /// connected to, if it is still in range.
+    pub async fn stop(self) -> crate::Result<(), HotspotError> {
+        let stopped = self
+            .access_point
+            .stop()
+            .await
+            .map_err(|err| err.map_operation(HotspotError::Stop));
+        // Switching modes stops the access point anyway
+        let restored = self.restore().await;
+        stopped.and(restored)
+    }
+
+    async fn restore(&self) -> crate::Result<(), HotspotError> {
+        restore(
+            &self.device,
+            &self.previous_mode,
+            self.previous_network.as_ref(),
+        )
+        .await
+    }
+}
+
+async fn connected_network_id(device: &Device) -> Option<NetworkId> {
+    let station = Station::new(device.proxy.connection().clone(), device.object_path())
+        .await
+        .ok()?;
+    station.connected_network().await.ok()??.id()
+}
+
+async fn restore(
+    device: &Device,
+    previous_mode: &Mode,
+    previous_network: Option<&NetworkId>,
+) -> crate::Result<(), HotspotError> {
+    if *previous_mode != Mode::Station {
+        device
+            .set_mode(previous_mode.clone())
+            .await
+            .map_err(|err| err.map_operation(HotspotError::SetMode))?;
+        return Ok(());
+    }
+
+    let station = device
+        .switch_to_station()
+        .await
+        .map_err(|err| err.map_operation(HotspotError::SetMode))?;
+    let Some(previous_network) = previous_network else {
+        return Ok(());
+    };
+
+    // The station scans as soon as it appears, and may autoconnect to the network on its own.
+    let _ = timer::timeout(SCAN_TIMEOUT, station.wait_for_scan_complete()).await;
+    if station
+        .connected_network()
+        .await
+        .map_err(Error::into_operation)?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    let networks = station
+        .discovered_networks()
+        .await
+        .map_err(Error::into_operation)?;
+    let network = networks
+        .into_iter()
+        .map(|(network, _)| network)
+        .find(|network| network.id().as_ref() == Some(previous_network));
+    match network {
+        Some(network) => network
+            .connect()
+            .await
+            .map_err(|err| err.map_operation(HotspotError::Reconnect)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use futures_lite::{Stream, stream};
+
+use crate::{
+    Error,
+    access_point::AccessPoint,
+    error::hotspot::HotspotError,
+    timer,
+    wifi_qr::{WifiAuthentication, WifiQr},
+};
+
+// Without characters easily mistaken for one another when read from a screen.
+const PASSPHRASE_CHARSET: &[u8] = b"abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const PASSPHRASE_LENGTH: usize = 16;
+
+/// An access point whose passphrase changes on a schedule, for visitors.
+#[derive(Debug, Clone)]
+pub struct GuestHotspot {
+    access_point: AccessPoint,
+    ssid: String,
+    // Only ever replaced as a whole, a panic while it is locked can not leave it half written.
+    passphrase: Arc<Mutex<String>>,
+}
+
+impl GuestHotspot {
+    /// Start the access point with a freshly generated passphrase.
+    pub async fn start(
+        access_point: AccessPoint,
+        ssid: impl Into<String>,
+    ) -> crate::Result<Self, HotspotError> {
+        let ssid = ssid.into();
+        let passphrase = generate_passphrase(PASSPHRASE_LENGTH).map_err(Error::into_operation)?;
+        access_point
+            .start(&ssid, &passphrase)
+            .await
+            .map_err(|err| err.map_operation(HotspotError::Start))?;
+
+        Ok(Self {
+            access_point,
+            ssid,
+            passphrase: Arc::new(Mutex::new(passphrase)),
+        })
+    }
+
+    pub fn access_point(&self) -> &AccessPoint {
+        &self.access_point
+    }
+
+    /// The current credentials, to be shown as a QR code.
+    pub fn credentials(&self) -> WifiQr {
+        WifiQr {
+            ssid: self.ssid.clone(),
+            authentication: WifiAuthentication::Wpa,
+            password: Some(
+                self.passphrase
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone(),
+            ),
+            hidden: false,
+        }
+    }
+
+    /// Restart the access point with a new passphrase. iwd can not change the passphrase of a running access point,
+    /// so every client is disconnected and has to join again with the new credentials.
+    ///
+    /// If the access point does not start with the new passphrase, it is restarted with the previous one before
+    /// returning the error, leaving [`GuestHotspot::credentials`] valid.
+    pub async fn rotate(&self) -> crate::Result<WifiQr, HotspotError> {
+        let passphrase = generate_passphrase(PASSPHRASE_LENGTH).map_err(Error::into_operation)?;
+
+        self.access_point
+            .stop()
+            .await
+            .map_err(|err| err.map_operation(HotspotError::Stop))?;
+        if let Err(err) = self.access_point.start(&self.ssid, &passphrase).await {
+            let previous_passphrase = self
+                .passphrase
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone();
+            let _ = self
+                .access_point
+                .start(&self.ssid, &previous_passphrase)
+                .await;
+            return Err(err.map_operation(HotspotError::Start));
+        }
+
+        *self
+            .passphrase
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = passphrase;
+        Ok(self.credentials())
+    }
+
+    /// Rotate the passphrase every `period`, yielding the new credentials. Rotations only happen while the stream is
+    /// polled, a failed rotation is retried at the next period.
+    pub fn rotations(
+        &self,
+        period: Duration,
+    ) -> impl Stream<Item = crate::Result<WifiQr, HotspotError>> + Unpin + Send + 'static {
+        Box::pin(stream::unfold(self.clone(), move |hotspot| async move {
+            timer::sleep(period).await;
+            let credentials = hotspot.rotate().await;
+            Some((credentials, hotspot))
+        }))
+    }
+
+    pub async fn stop(self) -> crate::Result<(), HotspotError> {
+        self.access_point
+            .stop()
+            .await
+            .map_err(|err| err.map_operation(HotspotError::Stop))
+    }
+}
+
+/// A random passphrase of `length` letters and digits, without the ones looking alike.
+pub fn generate_passphrase(length: usize) -> crate::Result<String> {
+    // Rejection sampling keeps every character equally likely.
+    let limit = u8::MAX - u8::MAX % PASSPHRASE_CHARSET.len() as u8;
+
+    let mut passphrase = String::with_capacity(length);
+    let mut bytes = [0; 64];
+    while passphrase.len() < length {
+        getrandom::fill(&mut bytes).map_err(|err| Error::Io(err.into()))?;
+        passphrase.extend(
+            bytes
+                .iter()
+                .filter(|byte| **byte < limit)
+                .map(|byte| {
+                    char::from(PASSPHRASE_CHARSET[usize::from(*byte) % PASSPHRASE_CHARSET.len()])
+                })
+                .take(length - passphrase.len()),
+        );
+    }
+    Ok(passphrase)
+}
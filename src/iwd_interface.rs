@@ -1,38 +1,353 @@
-use zbus::{Connection, Proxy};
-use zvariant::OwnedObjectPath;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::{Arc, LazyLock, Mutex, MutexGuard, PoisonError, Weak},
+};
+
+use futures_lite::{StreamExt, future};
+use zbus::{
+    Connection, Proxy,
+    fdo::{InterfacesAddedStream, InterfacesRemovedStream, ObjectManagerProxy},
+    names::OwnedUniqueName,
+    proxy::{Builder, CacheProperties},
+};
+use zvariant::{OwnedObjectPath, OwnedValue};
 
 const DESTINATION: &str = "net.connman.iwd";
 
-pub trait IwdInterface: Sized {
+pub trait IwdInterface: Sized + Send {
     const INTERFACE: &str;
 
-    async fn new(connection: Connection, dbus_path: OwnedObjectPath) -> zbus::Result<Self>;
+    fn from_proxy(proxy: Arc<Proxy<'static>>) -> Self;
 
-    async fn proxy(
+    /// Build the object without fetching any of its properties, which are read on first use. Building it subscribes
+    /// to its `PropertiesChanged` signals, and the first object built on a connection also subscribes to iwd's
+    /// `InterfacesAdded` and `InterfacesRemoved` signals. Objects built for the same path share their proxy, and
+    /// therefore the properties it caches from `PropertiesChanged` signals, until iwd removes the interface from the
+    /// path.
+    fn new(
         connection: Connection,
         dbus_path: OwnedObjectPath,
-    ) -> zbus::Result<Proxy<'static>> {
-        Proxy::new_owned(connection, DESTINATION, dbus_path, Self::INTERFACE).await
+    ) -> impl Future<Output = crate::Result<Self>> + Send {
+        async move {
+            let proxy = shared_proxy(connection, dbus_path, Self::INTERFACE).await?;
+            Ok(Self::from_proxy(proxy))
+        }
+    }
+}
+
+type ProxyKey = (Option<OwnedUniqueName>, OwnedObjectPath, &'static str);
+
+static PROXIES: LazyLock<Mutex<HashMap<ProxyKey, Weak<Proxy<'static>>>>> =
+    LazyLock::new(Mutex::default);
+
+/// Connections whose `InterfacesAdded` and `InterfacesRemoved` signals evict entries of [`PROXIES`].
+static WATCHED_CONNECTIONS: LazyLock<Mutex<HashSet<OwnedUniqueName>>> =
+    LazyLock::new(Mutex::default);
+
+// Both are caches, which a panic while holding them cannot leave inconsistent.
+fn proxies() -> MutexGuard<'static, HashMap<ProxyKey, Weak<Proxy<'static>>>> {
+    PROXIES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn watched_connections() -> MutexGuard<'static, HashSet<OwnedUniqueName>> {
+    WATCHED_CONNECTIONS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+async fn shared_proxy(
+    connection: Connection,
+    dbus_path: OwnedObjectPath,
+    interface: &'static str,
+) -> crate::Result<Arc<Proxy<'static>>> {
+    let key = (connection.unique_name().cloned(), dbus_path, interface);
+    if let Some(proxy) = proxies().get(&key).and_then(Weak::upgrade) {
+        return Ok(proxy);
+    }
+
+    loop {
+        // Watch before caching, so that no removal of the interface goes unnoticed.
+        watch_interfaces(&connection).await?;
+
+        let proxy = Builder::<Proxy<'static>>::new(&connection)
+            .destination(DESTINATION)?
+            .path(key.1.clone())?
+            .interface(interface)?
+            .cache_properties(CacheProperties::Lazily)
+            .build()
+            .await?;
+
+        let mut proxies = proxies();
+        // Another task may have built the same proxy in the meantime.
+        if let Some(proxy) = proxies.get(&key).and_then(Weak::upgrade) {
+            return Ok(proxy);
+        }
+        // The watcher stops once no proxy is in use, which may have happened while building this one.
+        if key
+            .0
+            .as_ref()
+            .is_some_and(|unique_name| !watched_connections().contains(unique_name))
+        {
+            continue;
+        }
+
+        let proxy = Arc::new(proxy);
+        proxies.retain(|_, proxy| proxy.strong_count() > 0);
+        proxies.insert(key, Arc::downgrade(&proxy));
+        return Ok(proxy);
+    }
+}
+
+/// Stop sharing the proxy of the interface at `dbus_path`, e.g. because iwd removed the interface and added it back,
+/// leaving the properties cached by the proxy stale.
+pub(crate) fn forget_proxy(connection: &Connection, dbus_path: &OwnedObjectPath, interface: &str) {
+    let unique_name = connection.unique_name();
+    proxies().retain(|(proxy_unique_name, proxy_path, proxy_interface), _| {
+        proxy_unique_name.as_ref() != unique_name
+            || proxy_path != dbus_path
+            || *proxy_interface != interface
+    });
+}
+
+/// Stop sharing the proxies built so far on the connection, their cached properties belong to a previous iwd
+/// instance.
+pub(crate) fn forget_proxies(connection: &Connection) {
+    let unique_name = connection.unique_name();
+    proxies().retain(|(proxy_unique_name, _, _), _| proxy_unique_name.as_ref() != unique_name);
+}
+
+/// Spawn, once per connection, the task evicting the proxies of the interfaces iwd adds or removes.
+async fn watch_interfaces(connection: &Connection) -> crate::Result<()> {
+    let Some(unique_name) = connection.unique_name().cloned() else {
+        return Ok(());
+    };
+    if watched_connections().contains(&unique_name) {
+        return Ok(());
+    }
+
+    let object_manager = ObjectManagerProxy::builder(connection)
+        .destination(DESTINATION)?
+        .path("/")?
+        .build()
+        .await?;
+    let added = object_manager.receive_interfaces_added().await?;
+    let removed = object_manager.receive_interfaces_removed().await?;
+
+    if !watched_connections().insert(unique_name.clone()) {
+        return Ok(());
+    }
+    connection
+        .executor()
+        .spawn(
+            evict_changed_interfaces(connection.clone(), unique_name, added, removed),
+            "iwdrs proxy cache",
+        )
+        .detach();
+    Ok(())
+}
+
+/// Runs until no proxy of the connection is in use anymore, checked whenever iwd adds or removes an interface.
+async fn evict_changed_interfaces(
+    connection: Connection,
+    unique_name: OwnedUniqueName,
+    mut added: InterfacesAddedStream,
+    mut removed: InterfacesRemovedStream,
+) {
+    loop {
+        // `None` once a stream ends, `Some(None)` for an undecodable signal.
+        let added_interfaces = async {
+            let signal = added.next().await?;
+            Some(signal.args().ok().map(|args| {
+                let interfaces = args
+                    .interfaces_and_properties()
+                    .keys()
+                    .map(|interface| interface.to_string())
+                    .collect::<Vec<_>>();
+                (
+                    OwnedObjectPath::from(args.object_path().to_owned()),
+                    interfaces,
+                )
+            }))
+        };
+        let removed_interfaces = async {
+            let signal = removed.next().await?;
+            Some(signal.args().ok().map(|args| {
+                let interfaces = args
+                    .interfaces()
+                    .iter()
+                    .map(|interface| interface.to_string())
+                    .collect::<Vec<_>>();
+                (
+                    OwnedObjectPath::from(args.object_path().to_owned()),
+                    interfaces,
+                )
+            }))
+        };
+
+        let Some(change) = future::or(added_interfaces, removed_interfaces).await else {
+            break;
+        };
+        if let Some((path, interfaces)) = change {
+            for interface in interfaces {
+                forget_proxy(&connection, &path, &interface);
+            }
+        }
+
+        // Checked with the cache locked, see `shared_proxy`.
+        let proxies = proxies();
+        let in_use = proxies.iter().any(|((proxy_unique_name, _, _), proxy)| {
+            proxy_unique_name.as_ref() == Some(&unique_name) && proxy.strong_count() > 0
+        });
+        if !in_use {
+            watched_connections().remove(&unique_name);
+            return;
+        }
     }
+    watched_connections().remove(&unique_name);
 }
 
+/// Properties of one interface, as returned by `GetAll` and `GetManagedObjects`.
+pub(crate) type Properties = HashMap<String, OwnedValue>;
+
+/// Fetch every property of the proxy's interface in a single call.
+pub(crate) async fn get_all(proxy: &Proxy<'static>) -> crate::Result<Properties> {
+    let reply = proxy
+        .connection()
+        .call_method(
+            Some(DESTINATION),
+            proxy.path(),
+            Some("org.freedesktop.DBus.Properties"),
+            "GetAll",
+            &(proxy.interface()),
+        )
+        .await?;
+    Ok(reply.body().deserialize()?)
+}
+
+pub(crate) fn property<T: TryFrom<OwnedValue, Error = zvariant::Error>>(
+    properties: &Properties,
+    name: &str,
+) -> crate::Result<T> {
+    optional_property(properties, name)?.ok_or_else(|| crate::Error::missing_property(name))
+}
+
+pub(crate) fn optional_property<T: TryFrom<OwnedValue, Error = zvariant::Error>>(
+    properties: &Properties,
+    name: &str,
+) -> crate::Result<Option<T>> {
+    match properties.get(name) {
+        Some(value) => Ok(Some(T::try_from(value.try_clone()?)?)),
+        None => Ok(None),
+    }
+}
+
+/// Declares the type of an iwd interface, and optionally accessors for its properties.
+///
+/// Each property of the table generates a getter and a stream of its values, plus a setter returning the given
+/// operation error for writable properties:
+///
+/// ```text
+/// iwd_interface_impl!(Device, "net.connman.iwd.Device", {
+///     Name: String => name, name_stream;
+///     Powered: bool => is_powered, powered_stream, set_power -> SetPowerError;
+///     optional Frequency: u32 => frequency, frequency_stream;
+/// });
+/// ```
+///
+/// `optional` properties are not always exposed by iwd, their getter and stream yield `None` while absent.
 macro_rules! iwd_interface_impl {
     ($interface_ty:ident, $interface_name:expr) => {
         #[derive(Clone, Debug)]
         pub struct $interface_ty {
-            proxy: Proxy<'static>,
+            pub(crate) proxy: std::sync::Arc<Proxy<'static>>,
+        }
+
+        impl $interface_ty {
+            /// Path of the object on iwd's D-Bus service.
+            pub fn object_path(&self) -> zvariant::OwnedObjectPath {
+                self.proxy.path().to_owned().into()
+            }
         }
 
         impl crate::iwd_interface::IwdInterface for $interface_ty {
             const INTERFACE: &str = $interface_name;
 
-            async fn new(connection: Connection, dbus_path: OwnedObjectPath) -> zbus::Result<Self> {
-                Ok(Self {
-                    proxy: Self::proxy(connection, dbus_path).await?,
-                })
+            fn from_proxy(proxy: std::sync::Arc<Proxy<'static>>) -> Self {
+                Self { proxy }
             }
         }
     };
+    ($interface_ty:ident, $interface_name:expr, { $($properties:tt)* }) => {
+        crate::iwd_interface::iwd_interface_impl!($interface_ty, $interface_name);
+
+        impl $interface_ty {
+            crate::iwd_interface::iwd_properties!($($properties)*);
+        }
+    };
+}
+
+macro_rules! iwd_properties {
+    () => {};
+    (
+        $(#[$meta:meta])*
+        optional $property:ident: $property_ty:ty => $getter:ident, $stream:ident;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        pub async fn $getter(&self) -> crate::Result<Option<$property_ty>> {
+            crate::absent_as_none(self.proxy.get_property(stringify!($property)).await)
+        }
+
+        #[doc = concat!("Stream of the values of [`Self::", stringify!($getter), "`], starting with the current one.")]
+        pub async fn $stream(
+            &self,
+        ) -> crate::Result<
+            impl futures_lite::Stream<Item = crate::Result<Option<$property_ty>>> + Unpin + 'static,
+        > {
+            crate::optional_property_stream(
+                Proxy::clone(&self.proxy),
+                self.$getter().await,
+                stringify!($property),
+            )
+            .await
+        }
+
+        crate::iwd_interface::iwd_properties!($($rest)*);
+    };
+    (
+        $(#[$meta:meta])*
+        $property:ident: $property_ty:ty => $getter:ident, $stream:ident $(, $setter:ident -> $setter_error:ty)?;
+        $($rest:tt)*
+    ) => {
+        $(#[$meta])*
+        pub async fn $getter(&self) -> crate::Result<$property_ty> {
+            Ok(self.proxy.get_property(stringify!($property)).await?)
+        }
+
+        #[doc = concat!("Stream of the values of [`Self::", stringify!($getter), "`], starting with the current one.")]
+        pub async fn $stream(
+            &self,
+        ) -> crate::Result<
+            impl futures_lite::Stream<Item = crate::Result<$property_ty>> + Unpin + 'static,
+        > {
+            crate::property_stream(Proxy::clone(&self.proxy), self.$getter().await, stringify!($property))
+                .await
+        }
+
+        $(
+            #[doc = concat!("Set the value returned by [`Self::", stringify!($getter), "`].")]
+            pub async fn $setter(&self, value: $property_ty) -> crate::Result<(), $setter_error> {
+                self.proxy
+                    .set_property(stringify!($property), value)
+                    .await?;
+                Ok(())
+            }
+        )?
+
+        crate::iwd_interface::iwd_properties!($($rest)*);
+    };
 }
 
 pub(crate) use iwd_interface_impl;
+pub(crate) use iwd_properties;
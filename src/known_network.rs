@@ -1,40 +1,62 @@
-use zbus::{Connection, Proxy, Result};
-use zvariant::OwnedObjectPath;
-
-use crate::{iwd_interface::iwd_interface_impl, network::NetworkType};
-
-iwd_interface_impl!(KnownNetwork, "net.connman.iwd.KnownNetwork");
+use zbus::Proxy;
+
+use crate::{
+    Result,
+    error::known_network::{ForgetError, SetAutoConnectError},
+    iwd_interface::{Properties, get_all, iwd_interface_impl, optional_property, property},
+    network::{NetworkId, NetworkType},
+};
+
+iwd_interface_impl!(KnownNetwork, "net.connman.iwd.KnownNetwork", {
+    Name: String => name, name_stream;
+    Type: NetworkType => network_type, network_type_stream;
+    Hidden: bool => hidden, hidden_stream;
+    optional LastConnectedTime: String => last_connected_time, last_connected_time_stream;
+    AutoConnect: bool => get_autoconnect, autoconnect_stream, set_autoconnect -> SetAutoConnectError;
+});
 
 impl KnownNetwork {
-    pub async fn forget(&self) -> Result<()> {
-        self.proxy.call_method("Forget", &()).await?;
-        Ok(())
-    }
-
-    pub async fn name(&self) -> Result<String> {
-        self.proxy.get_property("Name").await
-    }
-
-    pub async fn network_type(&self) -> Result<NetworkType> {
-        self.proxy.get_property("Type").await
+    /// Identity of the network, decoded from its object path without any D-Bus call. It is equal to the one of the
+    /// [`crate::network::Network`]s this known network applies to.
+    pub fn id(&self) -> Option<NetworkId> {
+        NetworkId::from_path(self.proxy.path())
     }
 
-    pub async fn hidden(&self) -> Result<bool> {
-        let hidden: bool = self.proxy.get_property("Hidden").await?;
-        Ok(hidden)
+    pub async fn forget(&self) -> Result<(), ForgetError> {
+        self.proxy.call_method("Forget", &()).await?;
+        Ok(())
     }
 
-    pub async fn last_connected_time(&self) -> Result<String> {
-        self.proxy.get_property("LastConnectedTime").await
+    /// Fetch all the properties of the known network in a single call.
+    pub async fn info(&self) -> Result<KnownNetworkInfo> {
+        KnownNetworkInfo::from_properties(self.clone(), &get_all(&self.proxy).await?)
     }
+}
 
-    pub async fn set_autoconnect(&self, auto_connect: bool) -> Result<()> {
-        self.proxy.set_property("AutoConnect", auto_connect).await?;
-        Ok(())
-    }
+/// Snapshot of the properties of a [`KnownNetwork`].
+#[derive(Debug, Clone)]
+pub struct KnownNetworkInfo {
+    pub known_network: KnownNetwork,
+    pub name: String,
+    pub network_type: NetworkType,
+    pub hidden: bool,
+    /// `None` if iwd never connected to the network.
+    pub last_connected_time: Option<String>,
+    pub auto_connect: bool,
+}
 
-    pub async fn get_autoconnect(&self) -> Result<bool> {
-        let auto_connect: bool = self.proxy.get_property("AutoConnect").await?;
-        Ok(auto_connect)
+impl KnownNetworkInfo {
+    pub(crate) fn from_properties(
+        known_network: KnownNetwork,
+        properties: &Properties,
+    ) -> Result<Self> {
+        Ok(Self {
+            known_network,
+            name: property(properties, "Name")?,
+            network_type: property(properties, "Type")?,
+            hidden: property(properties, "Hidden")?,
+            last_connected_time: optional_property(properties, "LastConnectedTime")?,
+            auto_connect: property(properties, "AutoConnect")?,
+        })
     }
 }
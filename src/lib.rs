@@ -2,25 +2,39 @@ use futures_lite::{Stream, StreamExt, stream};
 use zbus::Proxy;
 use zvariant::OwnedValue;
 
+pub use error::{Error, Result};
+
 pub mod access_point;
 pub mod adapter;
 pub mod agent;
+pub mod airplane_mode;
+mod async_fd;
 pub mod daemon;
 pub mod device;
 pub mod error;
 pub mod hidden_network;
+pub mod hotspot;
 mod iwd_interface;
 pub mod known_network;
+pub mod link;
+pub mod mac_address;
 pub mod modes;
 pub mod network;
+pub mod radio;
+pub mod rfkill;
 pub mod session;
+mod settings;
+pub mod signal_strength;
 pub mod station;
+mod timer;
+mod unblock;
+pub mod wifi_qr;
 
 async fn property_stream<T: TryFrom<OwnedValue, Error = zvariant::Error> + Unpin>(
     proxy: Proxy<'static>,
-    initial_value: zbus::Result<T>,
+    initial_value: crate::Result<T>,
     property_name: &'static str,
-) -> zbus::Result<impl Stream<Item = zbus::Result<T>> + Unpin> {
+) -> crate::Result<impl Stream<Item = crate::Result<T>> + Unpin> {
     // `receive_property_changed` does not yield the initial value if another stream on the same property has already
     // been created and yielded a value. Therefore we manually add an initial value to the beginning of the stream
     // as most consumers will expect it to be there. Note that the first stream created for each property (per proxy)
@@ -30,7 +44,77 @@ async fn property_stream<T: TryFrom<OwnedValue, Error = zvariant::Error> + Unpin
             proxy
                 .receive_property_changed(property_name)
                 .await
-                .then(|property_changed| async move { property_changed.get().await }),
+                .then(|property_changed| async move { Ok(property_changed.get().await?) }),
+        ),
+    ))
+}
+
+/// Same as [`property_stream`] for properties that iwd only exposes some of the time, yields `None` while the
+/// property is absent.
+async fn optional_property_stream<T: TryFrom<OwnedValue, Error = zvariant::Error> + Unpin>(
+    proxy: Proxy<'static>,
+    initial_value: crate::Result<Option<T>>,
+    property_name: &'static str,
+) -> crate::Result<impl Stream<Item = crate::Result<Option<T>>> + Unpin> {
+    Ok(Box::pin(
+        stream::iter([initial_value]).chain(
+            proxy.receive_property_changed(property_name).await.then(
+                |property_changed| async move { absent_as_none(property_changed.get().await) },
+            ),
         ),
     ))
 }
+
+/// `None` if reading the property failed because iwd does not expose it right now, other failures are kept.
+fn absent_as_none<T>(result: zbus::Result<T>) -> crate::Result<Option<T>> {
+    match result.map_err(Error::from) {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::Iwd { name, .. })
+            if name == "org.freedesktop.DBus.Error.InvalidArgs"
+                || name == "org.freedesktop.DBus.Error.UnknownProperty" =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zbus::fdo;
+
+    use super::{Error, absent_as_none};
+
+    #[test]
+    fn only_absent_properties_read_as_none() {
+        let fdo_error = |error: fdo::Error| zbus::Error::FDO(Box::new(error));
+
+        assert!(matches!(absent_as_none(Ok(1)), Ok(Some(1))));
+        assert!(matches!(
+            absent_as_none::<u8>(Err(fdo_error(fdo::Error::InvalidArgs(
+                "no such property".to_string()
+            )))),
+            Ok(None)
+        ));
+        assert!(matches!(
+            absent_as_none::<u8>(Err(fdo_error(fdo::Error::UnknownProperty(
+                "Model".to_string()
+            )))),
+            Ok(None)
+        ));
+        assert!(matches!(
+            absent_as_none::<u8>(Err(fdo_error(fdo::Error::ServiceUnknown(
+                "net.connman.iwd".to_string()
+            )))),
+            Err(Error::DaemonNotRunning)
+        ));
+        assert!(matches!(
+            absent_as_none::<u8>(Err(zbus::Error::Variant(zvariant::Error::IncorrectType))),
+            Err(Error::TypeMismatch(_))
+        ));
+        assert!(matches!(
+            absent_as_none::<u8>(Err(zbus::Error::InterfaceNotFound)),
+            Err(Error::Transport(_))
+        ));
+    }
+}
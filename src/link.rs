@@ -0,0 +1,461 @@
+//! Kernel view of the network interface of a device, read from `/sys/class/net` and `getifaddrs`, and followed
+//! through rtnetlink.
+
+use std::{
+    ffi::{CStr, CString},
+    fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    path::Path,
+    str::FromStr,
+};
+
+use strum::EnumString;
+
+use crate::async_fd::AsyncFd;
+
+const SYSFS_NET: &str = "/sys/class/net";
+
+// `struct nlmsghdr`, `struct ifaddrmsg` and `struct rtattr` of `linux/netlink.h` and `linux/rtnetlink.h`.
+const NLMSG_HEADER_SIZE: usize = 16;
+const IFADDRMSG_SIZE: usize = 8;
+const RTATTR_HEADER_SIZE: usize = 4;
+// Large enough for a page of dumped addresses, the kernel truncates longer datagrams.
+const RECEIVE_BUFFER_SIZE: usize = 32 * 1024;
+
+/// RFC 2863 operational state of an interface.
+#[derive(Debug, Clone, PartialEq, Eq, EnumString, strum::Display)]
+#[non_exhaustive]
+pub enum OperState {
+    #[strum(serialize = "notpresent")]
+    NotPresent,
+    #[strum(serialize = "down")]
+    Down,
+    #[strum(serialize = "lowerlayerdown")]
+    LowerLayerDown,
+    #[strum(serialize = "testing")]
+    Testing,
+    /// The interface is up but waits for an external event, e.g. the end of a 4-way handshake.
+    #[strum(serialize = "dormant")]
+    Dormant,
+    #[strum(serialize = "up")]
+    Up,
+    /// The state the kernel reports as `unknown`, or a state unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
+}
+
+/// An address assigned to an interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InterfaceAddress {
+    pub address: IpAddr,
+    pub prefix_length: u8,
+}
+
+impl InterfaceAddress {
+    /// Whether the address is only valid on the link, i.e. `169.254.0.0/16` or `fe80::/10`, which do not mean that
+    /// the network configuration is done.
+    pub fn is_link_local(&self) -> bool {
+        match self.address {
+            IpAddr::V4(address) => address.is_link_local(),
+            IpAddr::V6(address) => address.is_unicast_link_local(),
+        }
+    }
+}
+
+/// Snapshot of the kernel state of a network interface.
+#[derive(Debug, Clone)]
+pub struct LinkInfo {
+    pub name: String,
+    pub ifindex: u32,
+    /// Name of the kernel driver, e.g. `iwlwifi`.
+    pub driver: Option<String>,
+    /// Index and name of the wireless phy, the latter matching [`crate::adapter::Adapter::name`].
+    pub phy_index: Option<u32>,
+    pub phy_name: Option<String>,
+    pub operstate: OperState,
+    /// `None` while the interface is down, the kernel not reporting the carrier then.
+    pub carrier: Option<bool>,
+    pub mtu: u32,
+    pub addresses: Vec<InterfaceAddress>,
+}
+
+impl LinkInfo {
+    /// Read the state of the interface named `name`, e.g. `wlan0`.
+    pub fn for_interface(name: &str) -> crate::Result<Self> {
+        let directory = Path::new(SYSFS_NET).join(name);
+        if !directory.exists() {
+            return Err(crate::Error::InvalidArgument(format!(
+                "no network interface named {name}"
+            )));
+        }
+
+        let operstate = read(&directory, "operstate")?;
+        Ok(Self {
+            name: name.to_string(),
+            ifindex: parse(&directory, "ifindex")?,
+            driver: fs::read_link(directory.join("device/driver"))
+                .ok()
+                .and_then(|driver| Some(driver.file_name()?.to_string_lossy().into_owned())),
+            phy_index: parse(&directory, "phy80211/index").ok(),
+            phy_name: read(&directory, "phy80211/name").ok(),
+            operstate: OperState::from_str(&operstate).unwrap_or(OperState::Unknown(operstate)),
+            // Reading the carrier of an interface which is down fails with EINVAL.
+            carrier: read(&directory, "carrier")
+                .ok()
+                .map(|carrier| carrier == "1"),
+            mtu: parse(&directory, "mtu")?,
+            addresses: addresses(name)?,
+        })
+    }
+}
+
+fn read(directory: &Path, file: &str) -> io::Result<String> {
+    Ok(fs::read_to_string(directory.join(file))?.trim().to_string())
+}
+
+fn parse<T: FromStr>(directory: &Path, file: &str) -> crate::Result<T> {
+    let value = read(directory, file)?;
+    value.parse().map_err(|_| {
+        let path = directory.join(file);
+        crate::Error::TypeMismatch(format!("unexpected {value} in {}", path.display()))
+    })
+}
+
+/// The IPv4 and IPv6 addresses of the interface named `name`.
+pub fn addresses(name: &str) -> crate::Result<Vec<InterfaceAddress>> {
+    let mut ifaddrs = std::ptr::null_mut();
+    // SAFETY: `getifaddrs` initializes the list on success, which is freed below.
+    if unsafe { libc::getifaddrs(&mut ifaddrs) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let mut addresses = Vec::new();
+    let mut current = ifaddrs;
+    while !current.is_null() {
+        // SAFETY: every node of the list is valid until `freeifaddrs`, and names are NUL terminated.
+        let ifaddr = unsafe { &*current };
+        current = ifaddr.ifa_next;
+
+        if ifaddr.ifa_name.is_null()
+            || unsafe { CStr::from_ptr(ifaddr.ifa_name) }.to_bytes() != name.as_bytes()
+        {
+            continue;
+        }
+        // SAFETY: the addresses are either null or valid socket addresses of the family they declare.
+        let (address, netmask) =
+            unsafe { (ip_address(ifaddr.ifa_addr), ip_address(ifaddr.ifa_netmask)) };
+        let Some(address) = address else {
+            continue;
+        };
+        let prefix_length = match netmask {
+            Some(IpAddr::V4(netmask)) => u32::from(netmask).count_ones(),
+            Some(IpAddr::V6(netmask)) => u128::from(netmask).count_ones(),
+            None => 0,
+        };
+        addresses.push(InterfaceAddress {
+            address,
+            prefix_length: prefix_length as u8,
+        });
+    }
+
+    // SAFETY: the list comes from `getifaddrs` and is not used past this point.
+    unsafe { libc::freeifaddrs(ifaddrs) };
+    Ok(addresses)
+}
+
+/// Index of the interface named `name`.
+pub(crate) fn ifindex(name: &str) -> io::Result<u32> {
+    let name = CString::new(name).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    // SAFETY: `name` is NUL terminated.
+    match unsafe { libc::if_nametoindex(name.as_ptr()) } {
+        0 => Err(io::Error::last_os_error()),
+        ifindex => Ok(ifindex),
+    }
+}
+
+/// An address reported by rtnetlink, along with its `IFA_F_*` flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct AddressMessage {
+    pub(crate) ifindex: u32,
+    pub(crate) address: InterfaceAddress,
+    pub(crate) flags: u32,
+}
+
+impl AddressMessage {
+    /// Whether the address can be used, i.e. it is neither waiting for nor failed duplicate address detection, nor
+    /// deprecated.
+    pub(crate) fn is_usable(&self) -> bool {
+        self.flags & (libc::IFA_F_TENTATIVE | libc::IFA_F_DADFAILED | libc::IFA_F_DEPRECATED) == 0
+    }
+}
+
+/// Follows the addresses assigned to every interface through rtnetlink.
+pub(crate) struct AddressMonitor(AsyncFd<OwnedFd>);
+
+impl AddressMonitor {
+    /// Subscribe to the new IPv4 and IPv6 addresses, then request the current ones, which are reported first.
+    pub(crate) fn open() -> io::Result<Self> {
+        // SAFETY: plain socket creation, the descriptor is owned below.
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_RAW | libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK,
+                libc::NETLINK_ROUTE,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` was just created and is not owned elsewhere.
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        // SAFETY: `sockaddr_nl` is plain data, for which zeroes are valid.
+        let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        address.nl_groups = (libc::RTMGRP_IPV4_IFADDR | libc::RTMGRP_IPV6_IFADDR) as u32;
+        // SAFETY: `address` is a valid `sockaddr_nl` of the given size.
+        if unsafe {
+            libc::bind(
+                fd.as_raw_fd(),
+                (&raw const address).cast(),
+                size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        } != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut request = [0; NLMSG_HEADER_SIZE + IFADDRMSG_SIZE];
+        request[0..4].copy_from_slice(&((NLMSG_HEADER_SIZE + IFADDRMSG_SIZE) as u32).to_ne_bytes());
+        request[4..6].copy_from_slice(&libc::RTM_GETADDR.to_ne_bytes());
+        request[6..8]
+            .copy_from_slice(&((libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16).to_ne_bytes());
+        request[8..12].copy_from_slice(&1u32.to_ne_bytes());
+        // SAFETY: `request` is valid for its length, the kernel being the destination of the bound socket.
+        if unsafe { libc::send(fd.as_raw_fd(), request.as_ptr().cast(), request.len(), 0) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        AsyncFd::new(fd).map(Self)
+    }
+
+    /// The addresses of the next datagram, empty if it only holds other messages.
+    pub(crate) async fn next(&self) -> io::Result<Vec<AddressMessage>> {
+        let mut buffer = vec![0; RECEIVE_BUFFER_SIZE];
+        let length = self.0.read_with(|fd| receive(fd, &mut buffer)).await?;
+        parse_address_messages(&buffer[..length])
+    }
+}
+
+/// The `RTM_NEWADDR` messages of a netlink datagram, failing on an `NLMSG_ERROR` message.
+fn parse_address_messages(mut datagram: &[u8]) -> io::Result<Vec<AddressMessage>> {
+    let mut messages = Vec::new();
+    while datagram.len() >= NLMSG_HEADER_SIZE {
+        let length = u32::from_ne_bytes(datagram[0..4].try_into().unwrap()) as usize;
+        let kind = u16::from_ne_bytes(datagram[4..6].try_into().unwrap());
+        if length < NLMSG_HEADER_SIZE || length > datagram.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated netlink message",
+            ));
+        }
+
+        let payload = &datagram[NLMSG_HEADER_SIZE..length];
+        match kind {
+            libc::RTM_NEWADDR => messages.extend(parse_address_message(payload)),
+            kind if i32::from(kind) == libc::NLMSG_ERROR && payload.len() >= 4 => {
+                let error = i32::from_ne_bytes(payload[0..4].try_into().unwrap());
+                if error != 0 {
+                    return Err(io::Error::from_raw_os_error(-error));
+                }
+            }
+            _ => {}
+        }
+        datagram = &datagram[align(length).min(datagram.len())..];
+    }
+    Ok(messages)
+}
+
+fn parse_address_message(payload: &[u8]) -> Option<AddressMessage> {
+    let header = payload.get(..IFADDRMSG_SIZE)?;
+    let (family, prefix_length) = (i32::from(header[0]), header[1]);
+    let mut flags = u32::from(header[2]);
+    let ifindex = u32::from_ne_bytes(header[4..8].try_into().unwrap());
+
+    let (mut address, mut local) = (None, None);
+    let mut attributes = &payload[IFADDRMSG_SIZE..];
+    while attributes.len() >= RTATTR_HEADER_SIZE {
+        let length = usize::from(u16::from_ne_bytes([attributes[0], attributes[1]]));
+        let kind = u16::from_ne_bytes([attributes[2], attributes[3]]);
+        if length < RTATTR_HEADER_SIZE || length > attributes.len() {
+            break;
+        }
+
+        let data = &attributes[RTATTR_HEADER_SIZE..length];
+        let ip_address = match (family, data.len()) {
+            (libc::AF_INET, 4) => Some(IpAddr::from(<[u8; 4]>::try_from(data).unwrap())),
+            (libc::AF_INET6, 16) => Some(IpAddr::from(<[u8; 16]>::try_from(data).unwrap())),
+            _ => None,
+        };
+        match kind {
+            libc::IFA_ADDRESS => address = ip_address,
+            libc::IFA_LOCAL => local = ip_address,
+            // Supersedes the 8 bits of flags of the header.
+            libc::IFA_FLAGS if data.len() == 4 => {
+                flags = u32::from_ne_bytes(data.try_into().unwrap());
+            }
+            _ => {}
+        }
+        attributes = &attributes[align(length).min(attributes.len())..];
+    }
+
+    // `IFA_ADDRESS` is the peer of point-to-point IPv4 interfaces, `IFA_LOCAL` the address of the interface.
+    let address = match family {
+        libc::AF_INET => local.or(address),
+        _ => address.or(local),
+    }?;
+    Some(AddressMessage {
+        ifindex,
+        address: InterfaceAddress {
+            address,
+            prefix_length,
+        },
+        flags,
+    })
+}
+
+fn align(length: usize) -> usize {
+    length.next_multiple_of(4)
+}
+
+fn receive(fd: &OwnedFd, buffer: &mut [u8]) -> io::Result<usize> {
+    // SAFETY: `buffer` is valid for writes of its length.
+    let length = unsafe { libc::recv(fd.as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len(), 0) };
+    if length < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(length as usize)
+}
+
+/// # Safety
+///
+/// `sockaddr` must be null or point to a socket address of the family it declares.
+unsafe fn ip_address(sockaddr: *const libc::sockaddr) -> Option<IpAddr> {
+    if sockaddr.is_null() {
+        return None;
+    }
+
+    // SAFETY: guaranteed by the caller.
+    unsafe {
+        match i32::from((*sockaddr).sa_family) {
+            libc::AF_INET => {
+                let sockaddr = &*sockaddr.cast::<libc::sockaddr_in>();
+                Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(
+                    sockaddr.sin_addr.s_addr,
+                ))))
+            }
+            libc::AF_INET6 => {
+                let sockaddr = &*sockaddr.cast::<libc::sockaddr_in6>();
+                Some(IpAddr::V6(Ipv6Addr::from(sockaddr.sin6_addr.s6_addr)))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use super::{InterfaceAddress, parse_address_messages};
+
+    fn attribute(kind: u16, data: &[u8]) -> Vec<u8> {
+        let mut attribute = Vec::new();
+        attribute.extend(((4 + data.len()) as u16).to_ne_bytes());
+        attribute.extend(kind.to_ne_bytes());
+        attribute.extend(data);
+        attribute.resize(attribute.len().next_multiple_of(4), 0);
+        attribute
+    }
+
+    fn message(kind: u16, payload: &[u8]) -> Vec<u8> {
+        let mut message = Vec::new();
+        message.extend(((16 + payload.len()) as u32).to_ne_bytes());
+        message.extend(kind.to_ne_bytes());
+        message.extend([0; 10]);
+        message.extend(payload);
+        message
+    }
+
+    fn new_address(
+        family: i32,
+        prefix_length: u8,
+        ifindex: u32,
+        attributes: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let mut payload = vec![family as u8, prefix_length, 0, 0];
+        payload.extend(ifindex.to_ne_bytes());
+        payload.extend(attributes.concat());
+        message(libc::RTM_NEWADDR, &payload)
+    }
+
+    #[test]
+    fn parses_new_addresses() {
+        let ipv4 = new_address(
+            libc::AF_INET,
+            24,
+            3,
+            &[
+                attribute(libc::IFA_ADDRESS, &[10, 0, 0, 1]),
+                attribute(libc::IFA_LOCAL, &[192, 168, 1, 20]),
+            ],
+        );
+        let ipv6 = new_address(
+            libc::AF_INET6,
+            64,
+            3,
+            &[
+                attribute(
+                    libc::IFA_ADDRESS,
+                    &"2001:db8::1".parse::<Ipv6Addr>().unwrap().octets(),
+                ),
+                attribute(libc::IFA_FLAGS, &libc::IFA_F_TENTATIVE.to_ne_bytes()),
+            ],
+        );
+        let other = message(libc::RTM_DELADDR, &[0; 8]);
+
+        let messages = parse_address_messages(&[ipv4, other, ipv6].concat()).unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].ifindex, 3);
+        assert_eq!(
+            messages[0].address,
+            InterfaceAddress {
+                address: IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)),
+                prefix_length: 24,
+            }
+        );
+        assert!(messages[0].is_usable());
+        assert_eq!(
+            messages[1].address.address,
+            "2001:db8::1".parse::<IpAddr>().unwrap()
+        );
+        assert!(!messages[1].is_usable());
+    }
+
+    #[test]
+    fn fails_on_errors_and_truncated_messages() {
+        let error = message(libc::NLMSG_ERROR as u16, &(-libc::EPERM).to_ne_bytes());
+        assert_eq!(
+            parse_address_messages(&error).unwrap_err().raw_os_error(),
+            Some(libc::EPERM)
+        );
+
+        let acknowledgement = message(libc::NLMSG_ERROR as u16, &0i32.to_ne_bytes());
+        assert!(parse_address_messages(&acknowledgement).unwrap().is_empty());
+
+        let mut truncated = new_address(libc::AF_INET, 24, 3, &[]);
+        truncated[0] = 200;
+        assert!(parse_address_messages(&truncated).is_err());
+    }
+}
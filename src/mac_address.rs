@@ -0,0 +1,134 @@
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+use zvariant::{OwnedValue, Value};
+
+/// A 48 bits hardware address, formatted as `aa:bb:cc:dd:ee:ff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddress([u8; 6]);
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid MAC address: {0}")]
+pub struct ParseMacAddressError(String);
+
+impl MacAddress {
+    pub fn new(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    /// Organizationally Unique Identifier, the vendor part of the address.
+    pub fn oui(&self) -> [u8; 3] {
+        [self.0[0], self.0[1], self.0[2]]
+    }
+
+    /// Whether the address was assigned by software rather than by the manufacturer, which is the case of the
+    /// randomized addresses used by iwd.
+    pub fn is_locally_administered(&self) -> bool {
+        self.0[0] & 0b10 != 0
+    }
+
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0b1 != 0
+    }
+}
+
+impl FromStr for MacAddress {
+    type Err = ParseMacAddressError;
+
+    /// Parse an address whose octets are separated by `:` or `-`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseMacAddressError(s.to_string());
+
+        let mut octets = [0; 6];
+        let mut parts = s.split([':', '-']);
+        for octet in &mut octets {
+            let part = parts.next().ok_or_else(error)?;
+            if part.len() != 2 || !part.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                return Err(error());
+            }
+            *octet = u8::from_str_radix(part, 16).map_err(|_| error())?;
+        }
+        if parts.next().is_some() {
+            return Err(error());
+        }
+
+        Ok(Self(octets))
+    }
+}
+
+impl Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+impl TryFrom<OwnedValue> for MacAddress {
+    type Error = zvariant::Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+        let address: String = value.try_into()?;
+        Self::from_str(&address).map_err(|_| zvariant::Error::IncorrectType)
+    }
+}
+
+impl<'v> TryFrom<&'v Value<'v>> for MacAddress {
+    type Error = zvariant::Error;
+
+    fn try_from(value: &'v Value) -> Result<Self, Self::Error> {
+        let Value::Str(address) = value else {
+            return Err(zvariant::Error::IncorrectType);
+        };
+        Self::from_str(address.as_str()).map_err(|_| zvariant::Error::IncorrectType)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::MacAddress;
+
+    #[test]
+    fn parses_colon_and_dash_separated_addresses() {
+        let address = MacAddress::new([0x02, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e]);
+        assert_eq!(MacAddress::from_str("02:1a:2b:3c:4d:5e"), Ok(address));
+        assert_eq!(MacAddress::from_str("02-1A-2B-3C-4D-5E"), Ok(address));
+        assert!(address.is_locally_administered());
+        assert!(!address.is_multicast());
+        assert_eq!(address.oui(), [0x02, 0x1a, 0x2b]);
+
+        for invalid in [
+            "",
+            "02:1a:2b:3c:4d",
+            "02:1a:2b:3c:4d:5e:6f",
+            "02:1a:2b:3c:4d:5",
+            "002:1a:2b:3c:4d:5e",
+            "02:1a:2b:3c:4d:5g",
+            "+2:1a:2b:3c:4d:5e",
+        ] {
+            assert!(MacAddress::from_str(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        for address in [
+            "00:00:00:00:00:00",
+            "ff:ff:ff:ff:ff:ff",
+            "a0:b1:c2:d3:e4:f5",
+        ] {
+            assert_eq!(MacAddress::from_str(address).unwrap().to_string(), address);
+        }
+        assert_eq!(
+            MacAddress::from_str("A0-B1-C2-D3-E4-F5")
+                .unwrap()
+                .to_string(),
+            "a0:b1:c2:d3:e4:f5"
+        );
+    }
+}
@@ -1,19 +1,32 @@
-use std::fmt::Display;
+use std::str::FromStr;
 
 use strum::EnumString;
+use zvariant::{OwnedValue, Value};
 
-#[derive(Debug, Clone, Copy, PartialEq, EnumString)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumString, strum::Display)]
 #[strum(ascii_case_insensitive)]
+#[non_exhaustive]
 pub enum Mode {
+    #[strum(serialize = "station")]
     Station,
+    #[strum(serialize = "ap")]
     Ap,
+    /// A mode unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
 }
 
-impl Display for Mode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Mode::Ap => write!(f, "ap"),
-            Mode::Station => write!(f, "station"),
-        }
+impl TryFrom<OwnedValue> for Mode {
+    type Error = zvariant::Error;
+
+    fn try_from(value: OwnedValue) -> std::result::Result<Self, Self::Error> {
+        let mode_string: String = value.try_into()?;
+        Self::from_str(&mode_string).map_err(|_| zvariant::Error::IncorrectType)
+    }
+}
+
+impl From<Mode> for Value<'_> {
+    fn from(mode: Mode) -> Self {
+        Value::from(mode.to_string())
     }
 }
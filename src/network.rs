@@ -1,82 +1,190 @@
 use std::str::FromStr;
 
-use futures_lite::Stream;
 use strum::EnumString;
-use zbus::{Connection, Proxy, Result as ZbusResult};
-use zvariant::{OwnedObjectPath, OwnedValue};
+use zbus::Proxy;
+use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
 
 use crate::{
+    Result,
     device::Device,
-    error::{IWDError, network::ConnectError},
-    iwd_interface::{IwdInterface, iwd_interface_impl},
+    error::network::ConnectError,
+    iwd_interface::{
+        IwdInterface, Properties, get_all, iwd_interface_impl, optional_property, property,
+    },
     known_network::KnownNetwork,
 };
 
-iwd_interface_impl!(Network, "net.connman.iwd.Network");
+iwd_interface_impl!(Network, "net.connman.iwd.Network", {
+    Name: String => name, name_stream;
+    Connected: bool => connected, connected_stream;
+    Device: OwnedObjectPath => device_path, device_path_stream;
+    Type: NetworkType => network_type, network_type_stream;
+    optional KnownNetwork: OwnedObjectPath => known_network_path, known_network_path_stream;
+});
 
 impl Network {
+    /// Identity of the network, decoded from its object path without any D-Bus call.
+    pub fn id(&self) -> Option<NetworkId> {
+        NetworkId::from_path(self.proxy.path())
+    }
+
     // Methods
-    pub async fn connect(&self) -> Result<(), IWDError<ConnectError>> {
+    pub async fn connect(&self) -> Result<(), ConnectError> {
         self.proxy.call_method("Connect", &()).await?;
         Ok(())
     }
 
-    // Properties
-
-    pub async fn name(&self) -> ZbusResult<String> {
-        let name: String = self.proxy.get_property("Name").await?;
-        Ok(name)
-    }
-
-    pub async fn connected(&self) -> ZbusResult<bool> {
-        self.proxy.get_property("Connected").await
-    }
-
-    pub async fn connected_stream(
-        &self,
-    ) -> zbus::Result<impl Stream<Item = zbus::Result<bool>> + Unpin + 'static> {
-        crate::property_stream(self.proxy.clone(), self.connected().await, "Connected").await
-    }
-
-    pub async fn device(&self) -> ZbusResult<Device> {
-        let device_path: OwnedObjectPath = self.proxy.get_property("Device").await?;
-
-        Device::new(self.proxy.connection().clone(), device_path).await
-    }
-
-    pub async fn network_type(&self) -> ZbusResult<NetworkType> {
-        self.proxy.get_property("Type").await
+    pub async fn device(&self) -> Result<Device> {
+        Device::new(self.proxy.connection().clone(), self.device_path().await?).await
     }
 
-    pub async fn known_network(&self) -> ZbusResult<Option<KnownNetwork>> {
-        if let Ok(known_network_path) = self
-            .proxy
-            .get_property::<OwnedObjectPath>("KnownNetwork")
-            .await
-        {
+    pub async fn known_network(&self) -> Result<Option<KnownNetwork>> {
+        if let Some(known_network_path) = self.known_network_path().await? {
             let network =
                 KnownNetwork::new(self.proxy.connection().clone(), known_network_path).await?;
             return Ok(Some(network));
         }
         Ok(None)
     }
+
+    /// Fetch all the properties of the network in a single call.
+    pub async fn info(&self) -> Result<NetworkInfo> {
+        NetworkInfo::from_properties(self.clone(), &get_all(&self.proxy).await?)
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, EnumString, strum::Display)]
+/// Snapshot of the properties of a [`Network`].
+#[derive(Debug, Clone)]
+pub struct NetworkInfo {
+    pub network: Network,
+    pub name: String,
+    pub connected: bool,
+    pub device: OwnedObjectPath,
+    pub network_type: NetworkType,
+    pub known_network: Option<OwnedObjectPath>,
+}
+
+impl NetworkInfo {
+    pub(crate) fn from_properties(network: Network, properties: &Properties) -> Result<Self> {
+        Ok(Self {
+            network,
+            name: property(properties, "Name")?,
+            connected: property(properties, "Connected")?,
+            device: property(properties, "Device")?,
+            network_type: property(properties, "Type")?,
+            known_network: optional_property(properties, "KnownNetwork")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EnumString, strum::Display)]
 #[strum(ascii_case_insensitive)]
+#[non_exhaustive]
 pub enum NetworkType {
     Open,
     Wep,
     Psk,
     #[strum(serialize = "8021x")]
     Eap,
+    /// A network type unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
 }
 
 impl TryFrom<OwnedValue> for NetworkType {
     type Error = zvariant::Error;
 
-    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+    fn try_from(value: OwnedValue) -> std::result::Result<Self, Self::Error> {
         let state_string: String = value.try_into()?;
         Self::from_str(&state_string).map_err(|_| zvariant::Error::IncorrectType)
     }
 }
+
+/// The SSID and type identifying a network, stable across scans and shared by a [`Network`] and its
+/// [`KnownNetwork`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NetworkId {
+    pub ssid_bytes: Vec<u8>,
+    pub network_type: NetworkType,
+}
+
+impl NetworkId {
+    /// Decode the `<hex encoded SSID>_<type>` last segment of a network or known network object path.
+    pub fn from_path(path: &ObjectPath<'_>) -> Option<Self> {
+        let (_, segment) = path.as_str().rsplit_once('/')?;
+        let (hex_ssid, network_type) = segment.rsplit_once('_')?;
+        if hex_ssid.is_empty() || hex_ssid.len() % 2 != 0 {
+            return None;
+        }
+
+        let ssid_bytes = (0..hex_ssid.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex_ssid.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+
+        Some(Self {
+            ssid_bytes,
+            network_type: NetworkType::from_str(network_type)
+                .unwrap_or(NetworkType::Unknown(network_type.to_string())),
+        })
+    }
+
+    /// The SSID, `None` if it is not valid UTF-8.
+    pub fn ssid(&self) -> Option<&str> {
+        std::str::from_utf8(&self.ssid_bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zvariant::ObjectPath;
+
+    use super::{NetworkId, NetworkType};
+
+    fn id(path: &str) -> Option<NetworkId> {
+        NetworkId::from_path(&ObjectPath::try_from(path).unwrap())
+    }
+
+    #[test]
+    fn decodes_network_paths() {
+        let network = id("/net/connman/iwd/0/4/4d794e6574_psk").unwrap();
+        assert_eq!(network.ssid(), Some("MyNet"));
+        assert_eq!(network.network_type, NetworkType::Psk);
+
+        let known_network = id("/net/connman/iwd/4d794e6574_psk").unwrap();
+        assert_eq!(known_network, network);
+
+        // Spaces and non UTF-8 bytes are hex encoded as well.
+        let network = id("/net/connman/iwd/0/4/4d79204e6574_open").unwrap();
+        assert_eq!(network.ssid(), Some("My Net"));
+        let network = id("/net/connman/iwd/0/4/ff00_open").unwrap();
+        assert_eq!(network.ssid_bytes, [0xff, 0x00]);
+        assert_eq!(network.ssid(), None);
+    }
+
+    #[test]
+    fn decodes_security_suffixes() {
+        for (suffix, network_type) in [
+            ("open", NetworkType::Open),
+            ("wep", NetworkType::Wep),
+            ("psk", NetworkType::Psk),
+            ("8021x", NetworkType::Eap),
+            ("owe", NetworkType::Unknown("owe".to_string())),
+        ] {
+            let path = format!("/net/connman/iwd/0/4/4d794e6574_{suffix}");
+            assert_eq!(id(&path).unwrap().network_type, network_type);
+        }
+    }
+
+    #[test]
+    fn rejects_paths_without_a_hex_ssid() {
+        for path in [
+            "/net/connman/iwd/0/4",
+            "/net/connman/iwd/0/4/_psk",
+            "/net/connman/iwd/0/4/4d7_psk",
+            "/net/connman/iwd/0/4/4g_psk",
+        ] {
+            assert_eq!(id(path), None, "{path}");
+        }
+    }
+}
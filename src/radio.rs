@@ -0,0 +1,188 @@
+//! Conversions between frequencies, channels and bands.
+
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Band {
+    Ghz2_4,
+    Ghz5,
+    Ghz6,
+}
+
+impl Band {
+    /// The band of a center frequency in MHz.
+    pub fn from_frequency(frequency_mhz: u32) -> Option<Self> {
+        match frequency_mhz {
+            2412..=2484 => Some(Self::Ghz2_4),
+            5160..=5885 => Some(Self::Ghz5),
+            5935..=7115 => Some(Self::Ghz6),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Band {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ghz2_4 => write!(f, "2.4 GHz"),
+            Self::Ghz5 => write!(f, "5 GHz"),
+            Self::Ghz6 => write!(f, "6 GHz"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ChannelWidth {
+    Mhz20,
+    Mhz40,
+    Mhz80,
+    Mhz160,
+    Mhz320,
+}
+
+impl ChannelWidth {
+    pub fn mhz(&self) -> u32 {
+        match self {
+            Self::Mhz20 => 20,
+            Self::Mhz40 => 40,
+            Self::Mhz80 => 80,
+            Self::Mhz160 => 160,
+            Self::Mhz320 => 320,
+        }
+    }
+}
+
+impl Display for ChannelWidth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} MHz", self.mhz())
+    }
+}
+
+/// A 20 MHz channel, numbered as in IEEE 802.11. Channel numbers are only unique within a band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Channel {
+    pub band: Band,
+    pub number: u16,
+}
+
+impl Channel {
+    /// The channel centered on `frequency_mhz`, `None` for frequencies outside the 2.4, 5 and 6 GHz channel
+    /// rasters.
+    pub fn from_frequency(frequency_mhz: u32) -> Option<Self> {
+        let band = Band::from_frequency(frequency_mhz)?;
+        let number = match (band, frequency_mhz) {
+            (Band::Ghz2_4, 2484) => 14,
+            (Band::Ghz2_4, 2412..=2472) => (frequency_mhz - 2407) / 5,
+            (Band::Ghz5, _) => (frequency_mhz - 5000) / 5,
+            (Band::Ghz6, 5935) => 2,
+            (Band::Ghz6, 5955..) => (frequency_mhz - 5950) / 5,
+            _ => return None,
+        };
+
+        let channel = Self {
+            band,
+            number: number as u16,
+        };
+        // Frequencies in between two channels
+        (channel.frequency() == Some(frequency_mhz)).then_some(channel)
+    }
+
+    /// Center frequency of the channel in MHz, `None` if the number is not a channel of the band.
+    pub fn frequency(&self) -> Option<u32> {
+        let number = u32::from(self.number);
+        match (self.band, self.number) {
+            (Band::Ghz2_4, 14) => Some(2484),
+            (Band::Ghz2_4, 1..=13) => Some(2407 + 5 * number),
+            (Band::Ghz5, 32..=177) => Some(5000 + 5 * number),
+            (Band::Ghz6, 2) => Some(5935),
+            (Band::Ghz6, 1..=233) if number % 4 == 1 => Some(5950 + 5 * number),
+            _ => None,
+        }
+    }
+
+    /// The widest channel this channel can be part of according to the 802.11 channelization. Whether it is
+    /// actually usable depends on the regulatory domain and the hardware.
+    pub fn max_width(&self) -> ChannelWidth {
+        match (self.band, self.number) {
+            (Band::Ghz2_4, 1..=13) => ChannelWidth::Mhz40,
+            (Band::Ghz5, 36..=64 | 100..=128) => ChannelWidth::Mhz160,
+            (Band::Ghz5, 132..=144 | 149..=161) => ChannelWidth::Mhz80,
+            // The second 320 MHz channelization, centered on 63, 127 and 191, reaches channel 221.
+            (Band::Ghz6, 1..=221) => ChannelWidth::Mhz320,
+            (Band::Ghz6, 225..=229) => ChannelWidth::Mhz40,
+            _ => ChannelWidth::Mhz20,
+        }
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Channel {} ({})", self.number, self.band)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Band, Channel, ChannelWidth};
+
+    #[test]
+    fn converts_frequencies_and_channels() {
+        for (frequency, band, number) in [
+            (2412, Band::Ghz2_4, 1),
+            (2472, Band::Ghz2_4, 13),
+            (2484, Band::Ghz2_4, 14),
+            (5180, Band::Ghz5, 36),
+            (5825, Band::Ghz5, 165),
+            (5885, Band::Ghz5, 177),
+            (5935, Band::Ghz6, 2),
+            (5955, Band::Ghz6, 1),
+            (6115, Band::Ghz6, 33),
+            (7115, Band::Ghz6, 233),
+        ] {
+            let channel = Channel { band, number };
+            assert_eq!(Channel::from_frequency(frequency), Some(channel));
+            assert_eq!(channel.frequency(), Some(frequency));
+        }
+
+        for frequency in [2400, 2414, 2477, 5000, 5182, 5940, 7120] {
+            assert_eq!(Channel::from_frequency(frequency), None);
+        }
+        for (band, number) in [
+            (Band::Ghz2_4, 0),
+            (Band::Ghz2_4, 15),
+            (Band::Ghz5, 178),
+            (Band::Ghz6, 3),
+            (Band::Ghz6, 237),
+        ] {
+            assert_eq!(Channel { band, number }.frequency(), None);
+        }
+    }
+
+    #[test]
+    fn max_width_follows_the_channelization() {
+        for (band, number, width) in [
+            (Band::Ghz2_4, 1, ChannelWidth::Mhz40),
+            (Band::Ghz2_4, 14, ChannelWidth::Mhz20),
+            (Band::Ghz5, 36, ChannelWidth::Mhz160),
+            (Band::Ghz5, 128, ChannelWidth::Mhz160),
+            (Band::Ghz5, 144, ChannelWidth::Mhz80),
+            (Band::Ghz5, 161, ChannelWidth::Mhz80),
+            (Band::Ghz5, 165, ChannelWidth::Mhz20),
+            (Band::Ghz6, 1, ChannelWidth::Mhz320),
+            (Band::Ghz6, 189, ChannelWidth::Mhz320),
+            (Band::Ghz6, 193, ChannelWidth::Mhz320),
+            (Band::Ghz6, 221, ChannelWidth::Mhz320),
+            (Band::Ghz6, 225, ChannelWidth::Mhz40),
+            (Band::Ghz6, 229, ChannelWidth::Mhz40),
+            (Band::Ghz6, 233, ChannelWidth::Mhz20),
+        ] {
+            assert_eq!(
+                Channel { band, number }.max_width(),
+                width,
+                "{band} {number}"
+            );
+        }
+    }
+}
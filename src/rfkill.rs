@@ -0,0 +1,286 @@
+//! Radio kill switches, read from `/sys/class/rfkill` and followed through `/dev/rfkill`.
+
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Read},
+    os::unix::fs::OpenOptionsExt,
+    path::Path,
+    str::FromStr,
+};
+
+use futures_lite::{Stream, stream};
+use strum::EnumString;
+
+use crate::{async_fd::AsyncFd, unblock::unblock};
+
+const SYSFS_RFKILL: &str = "/sys/class/rfkill";
+const DEV_RFKILL: &str = "/dev/rfkill";
+
+// `struct rfkill_event` of `linux/rfkill.h`, newer kernels append fields which are ignored.
+const EVENT_SIZE: usize = 8;
+const OP_ADD: u8 = 0;
+const OP_DEL: u8 = 1;
+const OP_CHANGE: u8 = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EnumString, strum::Display)]
+#[non_exhaustive]
+pub enum RfkillType {
+    #[strum(serialize = "all")]
+    All,
+    #[strum(serialize = "wlan")]
+    Wlan,
+    #[strum(serialize = "bluetooth")]
+    Bluetooth,
+    #[strum(serialize = "ultrawideband")]
+    Uwb,
+    #[strum(serialize = "wimax")]
+    Wimax,
+    #[strum(serialize = "wwan")]
+    Wwan,
+    #[strum(serialize = "gps")]
+    Gps,
+    #[strum(serialize = "fm")]
+    Fm,
+    #[strum(serialize = "nfc")]
+    Nfc,
+    /// A type unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
+}
+
+impl RfkillType {
+    fn from_event(kind: u8) -> Self {
+        match kind {
+            0 => Self::All,
+            1 => Self::Wlan,
+            2 => Self::Bluetooth,
+            3 => Self::Uwb,
+            4 => Self::Wimax,
+            5 => Self::Wwan,
+            6 => Self::Gps,
+            7 => Self::Fm,
+            8 => Self::Nfc,
+            kind => Self::Unknown(kind.to_string()),
+        }
+    }
+}
+
+/// Whether a radio may transmit, a hard block taking precedence over a soft one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BlockState {
+    Unblocked,
+    /// Blocked by software, e.g. `rfkill block`, which can be cleared with [`RfkillSwitch::unblock`].
+    SoftBlocked,
+    /// Blocked by a hardware switch or a firmware key, which only the user can clear.
+    HardBlocked,
+}
+
+/// A kill switch of the kernel, `rfkill<index>` in sysfs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RfkillSwitch {
+    pub index: u32,
+    /// e.g. `phy0` for the switch of a wireless adapter, or a platform driver name for laptop switches.
+    pub name: String,
+    pub kind: RfkillType,
+    /// The wireless adapter the switch belongs to, matching [`crate::adapter::Adapter::name`]. `None` for switches
+    /// which are not tied to an adapter, such as laptop Wi-Fi keys, which block every wireless adapter.
+    pub phy: Option<String>,
+    pub soft_blocked: bool,
+    pub hard_blocked: bool,
+}
+
+impl RfkillSwitch {
+    fn from_sysfs(index: u32) -> io::Result<Self> {
+        let directory = Path::new(SYSFS_RFKILL).join(format!("rfkill{index}"));
+        let read = |file: &str| -> io::Result<String> {
+            Ok(fs::read_to_string(directory.join(file))?.trim().to_string())
+        };
+
+        let is_phy = fs::read_link(directory.join("device/subsystem"))
+            .is_ok_and(|subsystem| subsystem.ends_with("ieee80211"));
+        let name = read("name")?;
+        let kind = read("type")?;
+
+        Ok(Self {
+            index,
+            phy: is_phy.then(|| name.clone()),
+            name,
+            kind: RfkillType::from_str(&kind).unwrap_or(RfkillType::Unknown(kind)),
+            soft_blocked: read("soft")? == "1",
+            hard_blocked: read("hard")? == "1",
+        })
+    }
+
+    pub fn state(&self) -> BlockState {
+        if self.hard_blocked {
+            BlockState::HardBlocked
+        } else if self.soft_blocked {
+            BlockState::SoftBlocked
+        } else {
+            BlockState::Unblocked
+        }
+    }
+
+    /// Whether the switch blocks the wireless adapter named `phy`.
+    pub fn affects_phy(&self, phy: &str) -> bool {
+        match &self.phy {
+            Some(switch_phy) => switch_phy == phy,
+            None => matches!(self.kind, RfkillType::Wlan | RfkillType::All),
+        }
+    }
+
+    /// Clear the soft block, which requires write access to sysfs, usually root.
+    pub fn unblock(&self) -> crate::Result<()> {
+        let path = Path::new(SYSFS_RFKILL).join(format!("rfkill{}/soft", self.index));
+        fs::write(path, "0")?;
+        Ok(())
+    }
+}
+
+/// The kill switches of the system, empty if the kernel has no rfkill support.
+pub fn switches() -> crate::Result<Vec<RfkillSwitch>> {
+    let entries = match fs::read_dir(SYSFS_RFKILL) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut switches = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let Some(index) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix("rfkill"))
+            .and_then(|index| index.parse().ok())
+        else {
+            continue;
+        };
+        match RfkillSwitch::from_sysfs(index) {
+            Ok(switch) => switches.push(switch),
+            // Removed while listing.
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+    switches.sort_by_key(|switch| switch.index);
+    Ok(switches)
+}
+
+/// The switches blocking the wireless adapter named `phy`, see [`RfkillSwitch::affects_phy`].
+pub fn switches_for_phy(phy: &str) -> crate::Result<Vec<RfkillSwitch>> {
+    Ok(switches()?
+        .into_iter()
+        .filter(|switch| switch.affects_phy(phy))
+        .collect())
+}
+
+/// The combined state of `switches`, the strongest block winning.
+pub fn block_state<'a>(switches: impl IntoIterator<Item = &'a RfkillSwitch>) -> BlockState {
+    switches
+        .into_iter()
+        .map(RfkillSwitch::state)
+        .max()
+        .unwrap_or(BlockState::Unblocked)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RfkillEvent {
+    /// Sent for every existing switch when the stream starts, then for every new switch.
+    Added(RfkillSwitch),
+    Removed {
+        index: u32,
+    },
+    /// The soft or hard block of the switch changed.
+    Changed(RfkillSwitch),
+}
+
+/// Follow the kill switches of the system through `/dev/rfkill`, which is readable by every user on most systems.
+///
+/// The stream ends after yielding a read error, as the device does not recover from them.
+pub fn events()
+-> crate::Result<impl Stream<Item = crate::Result<RfkillEvent>> + Unpin + Send + 'static> {
+    let watcher = EventWatcher {
+        device: AsyncFd::new(open_device()?)?,
+        switches: HashMap::new(),
+    };
+
+    Ok(Box::pin(stream::unfold(
+        Some(watcher),
+        |watcher| async move {
+            let mut watcher = watcher?;
+            let event = watcher.next().await?;
+            let watcher = event.is_ok().then_some(watcher);
+            Some((event, watcher))
+        },
+    )))
+}
+
+struct EventWatcher {
+    device: AsyncFd<fs::File>,
+    switches: HashMap<u32, RfkillSwitch>,
+}
+
+impl EventWatcher {
+    async fn next(&mut self) -> Option<crate::Result<RfkillEvent>> {
+        let mut buffer = [0; 64];
+        loop {
+            match self
+                .device
+                .read_with(|mut file| file.read(&mut buffer))
+                .await
+            {
+                Ok(0) => return None,
+                Ok(length) if length < EVENT_SIZE => continue,
+                Ok(_) => {}
+                Err(err) => return Some(Err(err.into())),
+            }
+
+            let index = u32::from_ne_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+            let (kind, op, soft, hard) = (buffer[4], buffer[5], buffer[6] != 0, buffer[7] != 0);
+            match op {
+                OP_ADD => {
+                    // The name and the adapter of the switch are only available from sysfs.
+                    let mut switch = unblock(move || RfkillSwitch::from_sysfs(index))
+                        .await
+                        .unwrap_or(RfkillSwitch {
+                            index,
+                            name: String::new(),
+                            kind: RfkillType::from_event(kind),
+                            phy: None,
+                            soft_blocked: soft,
+                            hard_blocked: hard,
+                        });
+                    switch.soft_blocked = soft;
+                    switch.hard_blocked = hard;
+                    self.switches.insert(index, switch.clone());
+                    return Some(Ok(RfkillEvent::Added(switch)));
+                }
+                OP_DEL => {
+                    self.switches.remove(&index);
+                    return Some(Ok(RfkillEvent::Removed { index }));
+                }
+                OP_CHANGE => {
+                    let Some(switch) = self.switches.get_mut(&index) else {
+                        continue;
+                    };
+                    if (switch.soft_blocked, switch.hard_blocked) == (soft, hard) {
+                        continue;
+                    }
+                    switch.soft_blocked = soft;
+                    switch.hard_blocked = hard;
+                    return Some(Ok(RfkillEvent::Changed(switch.clone())));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn open_device() -> io::Result<fs::File> {
+    OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK | libc::O_CLOEXEC)
+        .open(DEV_RFKILL)
+}
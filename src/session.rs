@@ -1,38 +1,106 @@
 use crate::{
-    access_point::{AccessPoint, AccessPointDiagnostics},
-    adapter::Adapter,
+    access_point::{AccessPoint, AccessPointDiagnostics, AccessPointInfo},
+    adapter::{Adapter, AdapterInfo},
     agent::{Agent, AgentManager},
     daemon::Daemon,
-    device::Device,
-    iwd_interface::{self, IwdInterface},
-    known_network::KnownNetwork,
-    station::{Station, StationDiagnostics},
+    device::{Device, DeviceInfo},
+    iwd_interface::{self, IwdInterface, Properties},
+    known_network::{KnownNetwork, KnownNetworkInfo},
+    network::Network,
+    station::{Station, StationDiagnostics, StationInfo},
+    timer,
 };
-use std::collections::HashMap;
+use futures_lite::{Stream, StreamExt};
+use std::{collections::HashMap, time::Duration};
 use uuid::Uuid;
-use zbus::{Connection, Proxy};
-use zvariant::{OwnedObjectPath, OwnedValue};
+use zbus::{
+    Connection, Proxy,
+    fdo::{InterfacesAddedStream, ObjectManagerProxy},
+};
+use zvariant::{ObjectPath, OwnedObjectPath, Value};
+
+pub mod events;
+pub mod supervised;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Session {
     connection: Connection,
-    pub(crate) objects: HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>>,
+    pub(crate) objects: ManagedObjects,
 }
 
-impl Session {
-    pub async fn new() -> zbus::Result<Self> {
-        let connection = Connection::system().await?;
+pub(crate) type ManagedObjects = HashMap<OwnedObjectPath, HashMap<String, Properties>>;
+
+pub(crate) async fn managed_objects(connection: &Connection) -> crate::Result<ManagedObjects> {
+    let proxy = Proxy::new(
+        connection,
+        "net.connman.iwd",
+        "/",
+        "org.freedesktop.DBus.ObjectManager",
+    )
+    .await?;
 
-        let proxy = Proxy::new(
-            &connection,
-            "net.connman.iwd",
-            "/",
-            "org.freedesktop.DBus.ObjectManager",
-        )
+    Ok(proxy.call("GetManagedObjects", &()).await?)
+}
+
+/// Subscribe to objects gaining interfaces, before triggering the change awaited with [`wait_for_interface`].
+pub(crate) async fn interfaces_added(
+    connection: &Connection,
+) -> crate::Result<InterfacesAddedStream> {
+    let object_manager = ObjectManagerProxy::builder(connection)
+        .destination("net.connman.iwd")?
+        .path("/")?
+        .build()
         .await?;
+    Ok(object_manager.receive_interfaces_added().await?)
+}
+
+/// Wait until the object at `dbus_path` implements `Object`.
+pub(crate) async fn wait_for_interface<Object: IwdInterface>(
+    connection: &Connection,
+    mut interfaces_added: InterfacesAddedStream,
+    dbus_path: &OwnedObjectPath,
+    timeout: Duration,
+) -> crate::Result<Object> {
+    let exists = managed_objects(connection)
+        .await?
+        .get(dbus_path)
+        .is_some_and(|interfaces| interfaces.contains_key(Object::INTERFACE));
+
+    if !exists {
+        let added = async {
+            while let Some(signal) = interfaces_added.next().await {
+                let Ok(args) = signal.args() else {
+                    continue;
+                };
+                if args.object_path().as_str() == dbus_path.as_str()
+                    && args
+                        .interfaces_and_properties()
+                        .contains_key(Object::INTERFACE)
+                {
+                    return true;
+                }
+            }
+            false
+        };
+        if timer::timeout(timeout, added).await != Some(true) {
+            return Err(crate::Error::Timeout);
+        }
+        // The cache of a proxy built before the interface was removed is stale, and the watcher of the cache may
+        // not have processed the signal yet.
+        iwd_interface::forget_proxy(connection, dbus_path, Object::INTERFACE);
+    }
+
+    Object::new(connection.clone(), dbus_path.clone()).await
+}
 
-        let objects: HashMap<OwnedObjectPath, HashMap<String, HashMap<String, OwnedValue>>> =
-            proxy.call("GetManagedObjects", &()).await?;
+impl Session {
+    pub async fn new() -> crate::Result<Self> {
+        let connection = Connection::system().await?;
+        Self::with_connection(connection).await
+    }
+
+    pub(crate) async fn with_connection(connection: Connection) -> crate::Result<Self> {
+        let objects = managed_objects(&connection).await?;
 
         Ok(Self {
             connection,
@@ -40,60 +108,284 @@ impl Session {
         })
     }
 
-    fn object_type(
+    /// Paths of the objects implementing the interface, sorted so that the order is the same across sessions.
+    fn object_type(&self, interface_type: &'static str) -> Vec<OwnedObjectPath> {
+        let mut paths: Vec<OwnedObjectPath> = self
+            .objects
+            .iter()
+            .filter(|(_, interfaces)| interfaces.contains_key(interface_type))
+            .map(|(path, _)| path.clone())
+            .collect();
+        paths.sort_by(|a, b| path_order(a).cmp(&path_order(b)));
+        paths
+    }
+
+    /// Build the snapshots of every object implementing the interface from a fresh `GetManagedObjects` call.
+    async fn collect_info<Interface: IwdInterface, Info>(
         &self,
-        interface_type: &'static str,
-    ) -> impl IntoIterator<Item = OwnedObjectPath> {
-        self.objects.iter().flat_map(move |(path, interfaces)| {
-            let path = path.clone();
-            interfaces
-                .iter()
-                .filter(move |(interface, _)| interface.as_str() == interface_type)
-                .map(move |_| path.clone())
-        })
+        from_properties: fn(Interface, &Properties) -> crate::Result<Info>,
+    ) -> crate::Result<Vec<Info>> {
+        let mut objects: Vec<_> = managed_objects(&self.connection)
+            .await?
+            .into_iter()
+            .collect();
+        objects.sort_by(|(a, _), (b, _)| path_order(a).cmp(&path_order(b)));
+
+        let mut results = Vec::new();
+        for (path, interfaces) in objects {
+            if let Some(properties) = interfaces.get(Interface::INTERFACE) {
+                let object = Interface::new(self.connection.clone(), path).await?;
+                results.push(from_properties(object, properties)?);
+            }
+        }
+        Ok(results)
     }
 
     async fn collect_interface<Output: iwd_interface::IwdInterface>(
         &self,
-    ) -> zbus::Result<Vec<Output>> {
-        let paths: Vec<_> = self.object_type(Output::INTERFACE).into_iter().collect();
-        let mut results = Vec::with_capacity(paths.len());
+    ) -> crate::Result<Vec<Output>> {
+        self.build_all(self.object_type(Output::INTERFACE)).await
+    }
+
+    async fn build_all<Object: IwdInterface>(
+        &self,
+        paths: Vec<OwnedObjectPath>,
+    ) -> crate::Result<Vec<Object>> {
+        let mut objects = Vec::with_capacity(paths.len());
         for path in paths {
-            results.push(Output::new(self.connection.clone(), path).await?);
+            objects.push(Object::new(self.connection.clone(), path).await?);
         }
-        Ok(results)
+        Ok(objects)
+    }
+
+    async fn build_optional<Object: IwdInterface>(
+        &self,
+        path: Option<OwnedObjectPath>,
+    ) -> crate::Result<Option<Object>> {
+        match path {
+            Some(path) => Ok(Some(Object::new(self.connection.clone(), path).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Build the object living at `dbus_path` without fetching its properties, e.g. a [`crate::network::Network`]
+    /// from a path received in a signal. It only subscribes to signals: the object's `PropertiesChanged`, and iwd's
+    /// `InterfacesAdded` and `InterfacesRemoved` for the first object built on the connection.
+    pub async fn object<Object: IwdInterface>(
+        &self,
+        dbus_path: OwnedObjectPath,
+    ) -> crate::Result<Object> {
+        Object::new(self.connection.clone(), dbus_path).await
+    }
+
+    /// The object implementing `Object` at the same path, iwd exposes the interfaces related to a device (station,
+    /// access point, diagnostics) on the device's path.
+    async fn object_at<Object: IwdInterface>(
+        &self,
+        dbus_path: &ObjectPath<'_>,
+    ) -> crate::Result<Option<Object>> {
+        let path = OwnedObjectPath::from(dbus_path.to_owned());
+        let exists = self
+            .objects
+            .get(&path)
+            .is_some_and(|interfaces| interfaces.contains_key(Object::INTERFACE));
+        self.build_optional(exists.then_some(path)).await
+    }
+
+    /// Every object implementing `Object` whose `property` holds `dbus_path`.
+    async fn objects_referencing<Object: IwdInterface>(
+        &self,
+        property: &str,
+        dbus_path: &ObjectPath<'_>,
+    ) -> crate::Result<Vec<Object>> {
+        let paths = self
+            .objects
+            .iter()
+            .filter(|(_, interfaces)| {
+                interfaces
+                    .get(Object::INTERFACE)
+                    .and_then(|properties| properties.get(property))
+                    .is_some_and(
+                        |value| matches!(&**value, Value::ObjectPath(path) if path == dbus_path),
+                    )
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        self.build_all(paths).await
+    }
+
+    /// The station of the device, `None` if the device is not in station mode.
+    ///
+    /// Like the other relations, this is resolved from the objects that existed when the session was created.
+    pub async fn station_for_device(&self, device: &Device) -> crate::Result<Option<Station>> {
+        self.object_at(device.proxy.path()).await
+    }
+
+    /// The device of the station.
+    pub async fn device_for_station(&self, station: &Station) -> crate::Result<Option<Device>> {
+        self.object_at(station.proxy.path()).await
+    }
+
+    /// The diagnostics of the station, `None` if iwd was built without them.
+    pub async fn station_diagnostics_for(
+        &self,
+        station: &Station,
+    ) -> crate::Result<Option<StationDiagnostics>> {
+        self.object_at(station.proxy.path()).await
+    }
+
+    /// The access point of the device, `None` if the device is not in access point mode.
+    pub async fn access_point_for_device(
+        &self,
+        device: &Device,
+    ) -> crate::Result<Option<AccessPoint>> {
+        self.object_at(device.proxy.path()).await
+    }
+
+    pub async fn devices_for_adapter(&self, adapter: &Adapter) -> crate::Result<Vec<Device>> {
+        self.objects_referencing("Adapter", adapter.proxy.path())
+            .await
+    }
+
+    /// The visible networks, on any station, that the known network applies to.
+    pub async fn networks_for_known_network(
+        &self,
+        known_network: &KnownNetwork,
+    ) -> crate::Result<Vec<Network>> {
+        self.objects_referencing("KnownNetwork", known_network.proxy.path())
+            .await
+    }
+
+    /// The adapter of the wireless phy named `name`, e.g. `phy0`.
+    pub async fn adapter_by_name(&self, name: &str) -> crate::Result<Option<Adapter>> {
+        let path = self
+            .object_type(Adapter::INTERFACE)
+            .into_iter()
+            .find(|path| {
+                self.property(path, Adapter::INTERFACE, "Name").is_some_and(
+                    |value| matches!(value, Value::Str(value) if value.as_str() == name),
+                )
+            });
+        self.build_optional(path).await
+    }
+
+    /// The device of the network interface named `name`, e.g. `wlan0`.
+    pub async fn device_by_name(&self, name: &str) -> crate::Result<Option<Device>> {
+        let path = self
+            .object_type(Device::INTERFACE)
+            .into_iter()
+            .find(|path| {
+                self.property(path, Device::INTERFACE, "Name").is_some_and(
+                    |value| matches!(value, Value::Str(value) if value.as_str() == name),
+                )
+            });
+        self.build_optional(path).await
+    }
+
+    /// The station of the network interface named `name`, `None` if it is not in station mode.
+    pub async fn station_by_interface(&self, name: &str) -> crate::Result<Option<Station>> {
+        match self.device_by_name(name).await? {
+            Some(device) => self.station_for_device(&device).await,
+            None => Ok(None),
+        }
+    }
+
+    /// The access point of the network interface named `name`, `None` if it is not in access point mode.
+    pub async fn access_point_by_interface(
+        &self,
+        name: &str,
+    ) -> crate::Result<Option<AccessPoint>> {
+        match self.device_by_name(name).await? {
+            Some(device) => self.access_point_for_device(&device).await,
+            None => Ok(None),
+        }
+    }
+
+    /// The station most likely to be the one in use: the first connected station, otherwise the first station whose
+    /// device is powered, otherwise the first station.
+    pub async fn primary_station(&self) -> crate::Result<Option<Station>> {
+        let rank = |path: &OwnedObjectPath| {
+            let connected = self
+                .property(path, Station::INTERFACE, "State")
+                .is_some_and(
+                    |value| matches!(value, Value::Str(state) if state.as_str() == "connected"),
+                );
+            let powered = self
+                .property(path, Device::INTERFACE, "Powered")
+                .is_some_and(|value| matches!(value, Value::Bool(true)));
+            match (connected, powered) {
+                (true, _) => 0,
+                (false, true) => 1,
+                (false, false) => 2,
+            }
+        };
+
+        let path = self
+            .object_type(Station::INTERFACE)
+            .into_iter()
+            .min_by_key(rank);
+        self.build_optional(path).await
     }
 
-    pub async fn adapters(&self) -> zbus::Result<Vec<Adapter>> {
+    fn property(
+        &self,
+        path: &OwnedObjectPath,
+        interface: &str,
+        property: &str,
+    ) -> Option<&Value<'static>> {
+        self.objects
+            .get(path)?
+            .get(interface)?
+            .get(property)
+            .map(|value| &**value)
+    }
+
+    pub async fn adapters(&self) -> crate::Result<Vec<Adapter>> {
         self.collect_interface().await
     }
 
-    pub async fn daemon(&self) -> zbus::Result<Daemon> {
+    pub async fn adapters_info(&self) -> crate::Result<Vec<AdapterInfo>> {
+        self.collect_info(AdapterInfo::from_properties).await
+    }
+
+    pub async fn daemon(&self) -> crate::Result<Daemon> {
         let path = OwnedObjectPath::try_from("/net/connman/iwd")?;
         Daemon::new(self.connection.clone(), path).await
     }
 
-    pub async fn devices(&self) -> zbus::Result<Vec<Device>> {
+    pub async fn devices(&self) -> crate::Result<Vec<Device>> {
         self.collect_interface().await
     }
 
-    pub async fn stations(&self) -> zbus::Result<Vec<Station>> {
+    pub async fn devices_info(&self) -> crate::Result<Vec<DeviceInfo>> {
+        self.collect_info(DeviceInfo::from_properties).await
+    }
+
+    pub async fn stations(&self) -> crate::Result<Vec<Station>> {
         self.collect_interface().await
     }
 
-    pub async fn stations_diagnostics(&self) -> zbus::Result<Vec<StationDiagnostics>> {
+    pub async fn stations_info(&self) -> crate::Result<Vec<StationInfo>> {
+        self.collect_info(StationInfo::from_properties).await
+    }
+
+    pub async fn stations_diagnostics(&self) -> crate::Result<Vec<StationDiagnostics>> {
         self.collect_interface().await
     }
 
-    pub async fn access_points(&self) -> zbus::Result<Vec<AccessPoint>> {
+    pub async fn access_points(&self) -> crate::Result<Vec<AccessPoint>> {
         self.collect_interface().await
     }
 
-    pub async fn access_points_diagnostics(&self) -> zbus::Result<Vec<AccessPointDiagnostics>> {
+    pub async fn access_points_info(&self) -> crate::Result<Vec<AccessPointInfo>> {
+        self.collect_info(AccessPointInfo::from_properties).await
+    }
+
+    pub async fn access_points_diagnostics(&self) -> crate::Result<Vec<AccessPointDiagnostics>> {
         self.collect_interface().await
     }
 
-    pub async fn register_agent(&self, agent: impl Agent) -> zbus::Result<AgentManager> {
+    pub async fn register_agent(&self, agent: impl Agent) -> crate::Result<AgentManager> {
         let path =
             OwnedObjectPath::try_from(format!("/iwdrs/agent/{}", Uuid::new_v4().as_simple()))?;
         let agent_manager = AgentManager::new(self.connection.clone(), path);
@@ -102,7 +394,29 @@ impl Session {
         Ok(agent_manager)
     }
 
-    pub async fn known_networks(&self) -> zbus::Result<Vec<KnownNetwork>> {
+    pub async fn known_networks(&self) -> crate::Result<Vec<KnownNetwork>> {
         self.collect_interface().await
     }
+
+    pub async fn known_networks_info(&self) -> crate::Result<Vec<KnownNetworkInfo>> {
+        self.collect_info(KnownNetworkInfo::from_properties).await
+    }
+
+    /// One stream for the changes happening on the stations, devices, adapters, access points and known networks
+    /// of this session. Changes are tracked on the objects that exist when this is called, except for known
+    /// networks being added and removed.
+    pub async fn events(
+        &self,
+    ) -> crate::Result<impl Stream<Item = crate::Result<events::Event>> + Unpin + Send + 'static>
+    {
+        events::event_stream(self).await
+    }
+}
+
+/// Sort key ordering paths such as `/net/connman/iwd/0/10` after `/net/connman/iwd/0/4`.
+fn path_order(path: &OwnedObjectPath) -> Vec<std::result::Result<u64, &str>> {
+    path.as_str()
+        .split('/')
+        .map(|segment| segment.parse().map_err(|_| segment))
+        .collect()
 }
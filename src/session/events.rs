@@ -0,0 +1,327 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_lite::{Stream, StreamExt, stream};
+use zbus::fdo::ObjectManagerProxy;
+use zvariant::OwnedObjectPath;
+
+use crate::{
+    access_point::AccessPoint,
+    adapter::Adapter,
+    device::Device,
+    iwd_interface::IwdInterface,
+    known_network::KnownNetwork,
+    modes::Mode,
+    network::Network,
+    session::Session,
+    station::{State, Station},
+};
+
+pub(super) type EventStream = Pin<Box<dyn Stream<Item = crate::Result<Event>> + Send>>;
+
+/// A change on one of the iwd objects of a [`Session`], see [`Session::events`].
+#[derive(Debug, Clone)]
+pub enum Event {
+    StationStateChanged {
+        station: Station,
+        state: State,
+    },
+    ScanStarted {
+        station: Station,
+    },
+    ScanFinished {
+        station: Station,
+    },
+    /// `network` is `None` once the station is no longer connected.
+    ConnectedNetworkChanged {
+        station: Station,
+        network: Option<Network>,
+    },
+    KnownNetworkAdded {
+        known_network: KnownNetwork,
+    },
+    /// The object is gone from iwd by the time this event is received, only its path is still meaningful.
+    KnownNetworkRemoved {
+        known_network: KnownNetwork,
+    },
+    KnownNetworkAutoConnectChanged {
+        known_network: KnownNetwork,
+        auto_connect: bool,
+    },
+    DevicePoweredChanged {
+        device: Device,
+        powered: bool,
+    },
+    DeviceModeChanged {
+        device: Device,
+        mode: Mode,
+    },
+    AdapterPoweredChanged {
+        adapter: Adapter,
+        powered: bool,
+    },
+    AccessPointStarted {
+        access_point: AccessPoint,
+    },
+    AccessPointStopped {
+        access_point: AccessPoint,
+    },
+}
+
+pub(super) async fn event_stream(session: &Session) -> crate::Result<EventStream> {
+    let mut streams: Vec<EventStream> = Vec::new();
+
+    for station in session.stations().await? {
+        let state_station = station.clone();
+        streams.push(Box::pin(changes(station.state_stream().await?).map(
+            move |state| {
+                state.map(|state| Event::StationStateChanged {
+                    station: state_station.clone(),
+                    state,
+                })
+            },
+        )));
+
+        let scanning_station = station.clone();
+        streams.push(Box::pin(changes(station.scanning_stream().await?).map(
+            move |scanning| {
+                let station = scanning_station.clone();
+                scanning.map(|scanning| match scanning {
+                    true => Event::ScanStarted { station },
+                    false => Event::ScanFinished { station },
+                })
+            },
+        )));
+
+        let connection = station.proxy.connection().clone();
+        streams.push(Box::pin(
+            changes(station.connected_network_path_stream().await?).then(move |network_path| {
+                let station = station.clone();
+                let connection = connection.clone();
+                async move {
+                    let network = match network_path? {
+                        Some(network_path) => Some(Network::new(connection, network_path).await?),
+                        None => None,
+                    };
+                    Ok(Event::ConnectedNetworkChanged { station, network })
+                }
+            }),
+        ));
+    }
+
+    for known_network in session.known_networks().await? {
+        streams.push(Box::pin(
+            changes(known_network.autoconnect_stream().await?).map(move |auto_connect| {
+                auto_connect.map(|auto_connect| Event::KnownNetworkAutoConnectChanged {
+                    known_network: known_network.clone(),
+                    auto_connect,
+                })
+            }),
+        ));
+    }
+    streams.extend(known_networks_added_removed(session).await?);
+
+    for device in session.devices().await? {
+        let powered_device = device.clone();
+        streams.push(Box::pin(changes(device.powered_stream().await?).map(
+            move |powered| {
+                powered.map(|powered| Event::DevicePoweredChanged {
+                    device: powered_device.clone(),
+                    powered,
+                })
+            },
+        )));
+
+        streams.push(Box::pin(changes(device.mode_stream().await?).map(
+            move |mode| {
+                mode.map(|mode| Event::DeviceModeChanged {
+                    device: device.clone(),
+                    mode,
+                })
+            },
+        )));
+    }
+
+    for adapter in session.adapters().await? {
+        streams.push(Box::pin(changes(adapter.powered_stream().await?).map(
+            move |powered| {
+                powered.map(|powered| Event::AdapterPoweredChanged {
+                    adapter: adapter.clone(),
+                    powered,
+                })
+            },
+        )));
+    }
+
+    for access_point in session.access_points().await? {
+        streams.push(Box::pin(changes(access_point.started_stream().await?).map(
+            move |started| {
+                let access_point = access_point.clone();
+                started.map(|started| match started {
+                    true => Event::AccessPointStarted { access_point },
+                    false => Event::AccessPointStopped { access_point },
+                })
+            },
+        )));
+    }
+
+    Ok(merge(streams))
+}
+
+/// Changes of a property, without its initial value.
+fn changes<T: Clone + PartialEq>(
+    stream: impl Stream<Item = crate::Result<T>>,
+) -> impl Stream<Item = crate::Result<T>> {
+    distinct(stream).skip(1)
+}
+
+async fn known_networks_added_removed(session: &Session) -> crate::Result<[EventStream; 2]> {
+    let object_manager = ObjectManagerProxy::builder(&session.connection)
+        .destination("net.connman.iwd")?
+        .path("/")?
+        .build()
+        .await?;
+
+    let connection = session.connection.clone();
+    let added = object_manager
+        .receive_interfaces_added()
+        .await?
+        .filter_map(|signal| {
+            let args = signal.args().ok()?;
+            args.interfaces_and_properties()
+                .keys()
+                .any(|interface| interface.as_str() == KnownNetwork::INTERFACE)
+                .then(|| OwnedObjectPath::from(args.object_path().to_owned()))
+        })
+        .then(move |path| {
+            let connection = connection.clone();
+            async move {
+                Ok(Event::KnownNetworkAdded {
+                    known_network: KnownNetwork::new(connection, path).await?,
+                })
+            }
+        });
+
+    let connection = session.connection.clone();
+    let removed = object_manager
+        .receive_interfaces_removed()
+        .await?
+        .filter_map(|signal| {
+            let args = signal.args().ok()?;
+            args.interfaces()
+                .iter()
+                .any(|interface| interface.as_str() == KnownNetwork::INTERFACE)
+                .then(|| OwnedObjectPath::from(args.object_path().to_owned()))
+        })
+        .then(move |path| {
+            let connection = connection.clone();
+            async move {
+                Ok(Event::KnownNetworkRemoved {
+                    known_network: KnownNetwork::new(connection, path).await?,
+                })
+            }
+        });
+
+    Ok([Box::pin(added), Box::pin(removed)])
+}
+
+fn distinct<T: Clone + PartialEq>(
+    stream: impl Stream<Item = crate::Result<T>>,
+) -> impl Stream<Item = crate::Result<T>> {
+    let mut last_value = None;
+    stream.filter_map(move |value| match value {
+        Ok(value) if last_value.as_ref() == Some(&value) => None,
+        Ok(value) => {
+            last_value = Some(value.clone());
+            Some(Ok(value))
+        }
+        Err(err) => Some(Err(err)),
+    })
+}
+
+/// Merge the streams as a balanced tree so that every stream gets polled as fairly as the others.
+fn merge(mut streams: Vec<EventStream>) -> EventStream {
+    match streams.len() {
+        0 => Box::pin(stream::empty()),
+        1 => streams.remove(0),
+        len => {
+            let second_half = streams.split_off(len / 2);
+            Box::pin(Merge {
+                first: Some(merge(streams)),
+                second: Some(merge(second_half)),
+                second_polled_first: false,
+            })
+        }
+    }
+}
+
+/// Yields the items of both streams as they come, alternating which one is polled first, and ends once both have
+/// ended. Unlike `stream::race`, streams which have ended are not polled again.
+struct Merge<S> {
+    first: Option<S>,
+    second: Option<S>,
+    second_polled_first: bool,
+}
+
+impl<S: Stream + Unpin> Stream for Merge<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        this.second_polled_first = !this.second_polled_first;
+        let (first, second) = match this.second_polled_first {
+            false => (&mut this.first, &mut this.second),
+            true => (&mut this.second, &mut this.first),
+        };
+
+        for side in [first, second] {
+            if let Some(stream) = side {
+                match stream.poll_next(cx) {
+                    Poll::Ready(Some(item)) => return Poll::Ready(Some(item)),
+                    Poll::Ready(None) => *side = None,
+                    Poll::Pending => {}
+                }
+            }
+        }
+
+        match (&this.first, &this.second) {
+            (None, None) => Poll::Ready(None),
+            _ => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_lite::{StreamExt, future, stream};
+
+    use super::{Event, EventStream, merge};
+
+    fn events(count: usize) -> EventStream {
+        Box::pin(stream::iter(0..count).map(|_| Err(crate::Error::Timeout)))
+    }
+
+    #[test]
+    fn merge_yields_every_item_then_ends() {
+        let merged = merge(vec![events(3), events(0), events(1), events(2)]);
+        let items: Vec<crate::Result<Event>> = future::block_on(merged.collect());
+        assert_eq!(items.len(), 6);
+    }
+
+    #[test]
+    fn merge_of_nothing_ends() {
+        assert!(future::block_on(merge(Vec::new()).next()).is_none());
+    }
+
+    #[test]
+    fn merge_ends_after_a_pending_stream_ends() {
+        let pending_once: EventStream = Box::pin(stream::once_future(async {
+            future::yield_now().await;
+            Err(crate::Error::Timeout)
+        }));
+        let merged = merge(vec![pending_once, events(1)]);
+        assert_eq!(future::block_on(merged.count()), 2);
+    }
+}
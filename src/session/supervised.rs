@@ -0,0 +1,300 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use async_broadcast::{InactiveReceiver, Sender};
+use futures_lite::{Stream, StreamExt};
+use zbus::{
+    Connection, Task,
+    fdo::{DBusProxy, NameOwnerChangedStream, Properties},
+    names::WellKnownName,
+};
+use zvariant::OwnedObjectPath;
+
+use crate::{
+    Error,
+    agent::{Agent, AgentManager},
+    device::Device,
+    iwd_interface::{self, IwdInterface},
+    session::Session,
+    station::{
+        Station,
+        signal_level_agent::{SignalLevelAgent, SignalLevelAgentManager},
+    },
+    timer,
+};
+
+const IWD_NAME: &str = "net.connman.iwd";
+
+// iwd may own its bus name slightly before all of its objects are exported.
+const REFRESH_ATTEMPTS: usize = 5;
+const REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DaemonEvent {
+    /// iwd (re)appeared on the bus. The session has been refreshed and the agents registered through the
+    /// [`SupervisedSession`] have been registered again, except the ones reported by
+    /// [`DaemonEvent::AgentRegistrationFailed`] right before.
+    DaemonAppeared,
+    /// iwd left the bus. Every object obtained before this event is stale.
+    DaemonVanished,
+    /// iwd appeared on the bus but its objects could not be fetched, [`SupervisedSession::session`] stays `None`
+    /// until iwd appears again.
+    RefreshFailed { error: String },
+    /// An agent could not be registered again after iwd appeared, e.g. the interface of a signal level agent is
+    /// gone or not in station mode anymore. The agent is kept and registered again the next time iwd appears.
+    AgentRegistrationFailed {
+        dbus_path: OwnedObjectPath,
+        error: String,
+    },
+}
+
+/// A signal level agent, registered again on the station of the same network interface, as iwd may give the
+/// station another object path when it restarts.
+#[derive(Debug, Clone)]
+struct SupervisedSignalLevelAgent {
+    interface_name: String,
+    dbus_path: OwnedObjectPath,
+    levels: Vec<i16>,
+}
+
+#[derive(Debug, Default)]
+struct SupervisorState {
+    session: Option<Session>,
+    agents: Vec<AgentManager>,
+    signal_level_agents: Vec<SupervisedSignalLevelAgent>,
+}
+
+// The state stays consistent even if a holder of the lock panicked, every update being a single assignment.
+fn lock(state: &Mutex<SupervisorState>) -> MutexGuard<'_, SupervisorState> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A session which follows iwd across restarts.
+///
+/// It waits for iwd to show up on the bus, fetches a fresh [`Session`] every time iwd (re)appears and registers
+/// again every agent and signal level agent registered through it.
+#[derive(Debug)]
+pub struct SupervisedSession {
+    state: Arc<Mutex<SupervisorState>>,
+    events: InactiveReceiver<DaemonEvent>,
+    _watcher: Task<()>,
+}
+
+impl SupervisedSession {
+    /// Connect to the system bus and wait up to `timeout` for iwd to appear on it.
+    pub async fn new(timeout: Duration) -> crate::Result<Self> {
+        let connection = Connection::system().await?;
+        let dbus = DBusProxy::new(&connection).await?;
+
+        // Subscribe before checking for the name, so that iwd showing up in between is not missed.
+        let mut owner_changes = dbus
+            .receive_name_owner_changed_with_args(&[(0, IWD_NAME)])
+            .await?;
+
+        if !dbus
+            .name_has_owner(WellKnownName::from_static_str_unchecked(IWD_NAME).into())
+            .await?
+        {
+            let appeared = async {
+                while let Some(signal) = owner_changes.next().await {
+                    if signal.args().is_ok_and(|args| args.new_owner().is_some()) {
+                        return true;
+                    }
+                }
+                false
+            };
+            if timer::timeout(timeout, appeared).await != Some(true) {
+                return Err(Error::DaemonNotRunning);
+            }
+        }
+
+        let state = Arc::new(Mutex::new(SupervisorState {
+            session: Some(refresh(&connection).await?),
+            ..Default::default()
+        }));
+
+        let (mut sender, receiver) = async_broadcast::broadcast(8);
+        sender.set_overflow(true);
+        sender.set_await_active(false);
+
+        let watcher = connection.executor().spawn(
+            watch(connection.clone(), state.clone(), sender, owner_changes),
+            "iwdrs-supervised-session",
+        );
+
+        Ok(Self {
+            state,
+            events: receiver.deactivate(),
+            _watcher: watcher,
+        })
+    }
+
+    /// The session for the currently running iwd instance, `None` while iwd is gone.
+    pub fn session(&self) -> Option<Session> {
+        lock(&self.state).session.clone()
+    }
+
+    /// Stream of iwd appearing and vanishing from the bus.
+    pub fn events(&self) -> impl Stream<Item = DaemonEvent> + Unpin + 'static {
+        self.events.activate_cloned()
+    }
+
+    /// Same as [`Session::register_agent`], the agent gets registered again whenever iwd restarts.
+    pub async fn register_agent(&self, agent: impl Agent) -> crate::Result<AgentManager> {
+        let session = self.session().ok_or(Error::DaemonNotRunning)?;
+        let agent_manager = session.register_agent(agent).await?;
+
+        lock(&self.state).agents.push(agent_manager.clone());
+        Ok(agent_manager)
+    }
+
+    /// Unregister an agent registered through [`SupervisedSession::register_agent`], `A` being the type it was
+    /// registered with, so that it does not get registered again.
+    pub async fn unregister_agent<A: Agent>(
+        &self,
+        agent_manager: AgentManager,
+    ) -> crate::Result<()> {
+        lock(&self.state)
+            .agents
+            .retain(|known| known.dbus_path != agent_manager.dbus_path);
+        agent_manager.unregister_agent::<A>().await
+    }
+
+    /// Same as [`Station::register_signal_level_agent`], the agent gets registered again on the station of the
+    /// same network interface whenever iwd restarts.
+    pub async fn register_signal_level_agent(
+        &self,
+        station: &Station,
+        levels: Vec<i16>,
+        agent: impl SignalLevelAgent,
+    ) -> crate::Result<SignalLevelAgentManager> {
+        let device = Device::new(station.proxy.connection().clone(), station.object_path()).await?;
+        let interface_name = device.name().await?;
+        let manager = station.register_signal_level_agent(levels, agent).await?;
+
+        lock(&self.state)
+            .signal_level_agents
+            .push(SupervisedSignalLevelAgent {
+                interface_name,
+                dbus_path: manager.dbus_path.clone(),
+                levels: manager.levels.clone(),
+            });
+        Ok(manager)
+    }
+
+    /// Unregister a signal level agent registered through
+    /// [`SupervisedSession::register_signal_level_agent`] so that it does not get registered again.
+    pub async fn unregister_signal_level_agent(
+        &self,
+        manager: SignalLevelAgentManager,
+    ) -> crate::Result<()> {
+        lock(&self.state)
+            .signal_level_agents
+            .retain(|agent| agent.dbus_path != manager.dbus_path);
+        manager.unregister().await
+    }
+}
+
+async fn watch(
+    connection: Connection,
+    state: Arc<Mutex<SupervisorState>>,
+    sender: Sender<DaemonEvent>,
+    mut owner_changes: NameOwnerChangedStream,
+) {
+    while let Some(signal) = owner_changes.next().await {
+        let Ok(args) = signal.args() else {
+            continue;
+        };
+
+        if args.old_owner().is_some() {
+            lock(&state).session = None;
+            iwd_interface::forget_proxies(&connection);
+            let _ = sender.try_broadcast(DaemonEvent::DaemonVanished);
+        }
+
+        if args.new_owner().is_some() {
+            let session = match refresh(&connection).await {
+                Ok(session) => session,
+                Err(err) => {
+                    let _ = sender.try_broadcast(DaemonEvent::RefreshFailed {
+                        error: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            let (agents, signal_level_agents) = {
+                let mut state = lock(&state);
+                state.session = Some(session.clone());
+                (state.agents.clone(), state.signal_level_agents.clone())
+            };
+
+            // Agents which are not served anymore, e.g. a dropped signal level stream, are forgotten.
+            for agent in agents {
+                if !is_served(&connection, &agent.dbus_path).await {
+                    lock(&state)
+                        .agents
+                        .retain(|known| known.dbus_path != agent.dbus_path);
+                } else if let Err(err) = agent.register().await {
+                    let _ = sender.try_broadcast(DaemonEvent::AgentRegistrationFailed {
+                        dbus_path: agent.dbus_path,
+                        error: err.to_string(),
+                    });
+                }
+            }
+            for agent in signal_level_agents {
+                if !is_served(&connection, &agent.dbus_path).await {
+                    lock(&state)
+                        .signal_level_agents
+                        .retain(|known| known.dbus_path != agent.dbus_path);
+                } else if let Err(err) = register_signal_level_agent(&session, &agent).await {
+                    let _ = sender.try_broadcast(DaemonEvent::AgentRegistrationFailed {
+                        dbus_path: agent.dbus_path,
+                        error: err.to_string(),
+                    });
+                }
+            }
+
+            let _ = sender.try_broadcast(DaemonEvent::DaemonAppeared);
+        }
+    }
+}
+
+/// Whether an object is still served at `dbus_path`, the object server adding the `Properties` interface to each of
+/// them.
+async fn is_served(connection: &Connection, dbus_path: &OwnedObjectPath) -> bool {
+    connection
+        .object_server()
+        .interface::<_, Properties>(dbus_path)
+        .await
+        .is_ok()
+}
+
+async fn register_signal_level_agent(
+    session: &Session,
+    agent: &SupervisedSignalLevelAgent,
+) -> crate::Result<()> {
+    let station = session
+        .station_by_interface(&agent.interface_name)
+        .await?
+        .ok_or(Error::ObjectVanished)?;
+    station
+        .register_signal_level_agent_path(&agent.dbus_path, &agent.levels)
+        .await
+}
+
+/// A session for the iwd instance which just appeared, failing with the last error once every attempt failed.
+async fn refresh(connection: &Connection) -> crate::Result<Session> {
+    let mut attempt = 1;
+    loop {
+        match Session::with_connection(connection.clone()).await {
+            Ok(session) => return Ok(session),
+            Err(err) if attempt == REFRESH_ATTEMPTS => return Err(err),
+            Err(_) => {}
+        }
+        attempt += 1;
+        timer::sleep(REFRESH_INTERVAL).await;
+    }
+}
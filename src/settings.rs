@@ -0,0 +1,101 @@
+//! Values of the settings files iwd reads with ell's `l_settings`.
+
+use std::{
+    fs::{self, OpenOptions, Permissions},
+    io::{self, Write},
+    os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    path::Path,
+};
+
+/// Write a settings file holding secrets, readable by its owner only, creating its directory if needed.
+///
+/// An existing file gets its permissions fixed before the content is written, the mode of
+/// [`OpenOptions`] only applying to new files.
+pub(crate) fn write_secret_file(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    file.set_permissions(Permissions::from_mode(0o600))?;
+    file.write_all(content.as_bytes())
+}
+
+/// Whether `value` can be written to a settings file: `l_settings` has escapes for newlines, tabs and carriage
+/// returns only, other control characters can not be encoded.
+pub(crate) fn is_encodable(value: &str) -> bool {
+    !value
+        .chars()
+        .any(|c| c.is_control() && !matches!(c, '\n' | '\t' | '\r'))
+}
+
+/// Escape `value` the way `l_settings` unescapes it: backslashes, newlines, tabs, carriage returns, and a leading
+/// space which would otherwise be trimmed.
+pub(crate) fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, c) in value.chars().enumerate() {
+        match c {
+            ' ' if i == 0 => escaped.push_str("\\s"),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, Permissions},
+        os::unix::fs::PermissionsExt,
+    };
+
+    use super::{escape_value, is_encodable, write_secret_file};
+
+    #[test]
+    fn escapes_what_l_settings_unescapes() {
+        assert_eq!(escape_value("plain value"), "plain value");
+        assert_eq!(escape_value(" leading"), "\\sleading");
+        assert_eq!(escape_value("trailing "), "trailing ");
+        assert_eq!(escape_value("back\\slash"), "back\\\\slash");
+        assert_eq!(escape_value("a\nInjected=1\tb\r"), "a\\nInjected=1\\tb\\r");
+        assert_eq!(escape_value("pässwörd"), "pässwörd");
+    }
+
+    #[test]
+    fn rejects_control_characters_without_escape() {
+        assert!(is_encodable("tab\tnewline\ncr\r"));
+        assert!(!is_encodable("nul\0"));
+        assert!(!is_encodable("bell\u{7}"));
+        assert!(!is_encodable("escape\u{1b}"));
+    }
+
+    #[test]
+    fn secret_files_are_readable_by_their_owner_only() {
+        let directory = std::env::temp_dir().join(format!("iwdrs-{}-settings", std::process::id()));
+        let path = directory.join("nested/MyNet.psk");
+
+        write_secret_file(&path, "[Security]\nPassphrase=old\n").unwrap();
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+
+        fs::set_permissions(&path, Permissions::from_mode(0o644)).unwrap();
+        write_secret_file(&path, "[Security]\nPassphrase=new\n").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(content, "[Security]\nPassphrase=new\n");
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+/// Strength of a received signal, ordered from the weakest to the strongest.
+///
+/// iwd reports the strength of scanned networks in 100 * dBm and the RSSI of the connected network in dBm, both
+/// end up in this type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignalStrength(i16);
+
+impl SignalStrength {
+    pub fn from_dbm(dbm: i16) -> Self {
+        Self(dbm.saturating_mul(100))
+    }
+
+    /// `centi_dbm` is in 100 * dBm, as found in `GetOrderedNetworks` and `GetHiddenAccessPoints` replies.
+    pub fn from_centi_dbm(centi_dbm: i16) -> Self {
+        Self(centi_dbm)
+    }
+
+    /// Strength in dBm, rounded down.
+    pub fn dbm(&self) -> i16 {
+        self.0.div_euclid(100)
+    }
+
+    /// Strength in 100 * dBm.
+    pub fn centi_dbm(&self) -> i16 {
+        self.0
+    }
+
+    /// Quality in percent on the [`QualityScale::default`] scale.
+    pub fn quality(&self) -> u8 {
+        self.quality_on(QualityScale::default())
+    }
+
+    /// Quality in percent, 0 at or below `scale.min_dbm` and 100 at or above `scale.max_dbm`.
+    pub fn quality_on(&self, scale: QualityScale) -> u8 {
+        let min = i32::from(scale.min_dbm) * 100;
+        let max = i32::from(scale.max_dbm) * 100;
+        if max <= min {
+            return if i32::from(self.0) >= max { 100 } else { 0 };
+        }
+
+        let quality = (i32::from(self.0).clamp(min, max) - min) * 100 / (max - min);
+        quality as u8
+    }
+
+    /// Number of bars out of 4, using the same thresholds as `iwctl` (-60, -67 and -75 dBm) with 0 bars below
+    /// -85 dBm.
+    pub fn bars(&self) -> u8 {
+        match self.0 {
+            -6000.. => 4,
+            -6700.. => 3,
+            -7500.. => 2,
+            -8500.. => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl Display for SignalStrength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} dBm", self.dbm())
+    }
+}
+
+/// Range of signal strengths mapped to a 0 to 100% quality by [`SignalStrength::quality_on`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QualityScale {
+    /// Strength in dBm considered as 0% quality.
+    pub min_dbm: i16,
+    /// Strength in dBm considered as 100% quality.
+    pub max_dbm: i16,
+}
+
+impl Default for QualityScale {
+    /// From -100 to -40 dBm, the scale used by NetworkManager.
+    fn default() -> Self {
+        Self {
+            min_dbm: -100,
+            max_dbm: -40,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QualityScale, SignalStrength};
+
+    #[test]
+    fn converts_dbm() {
+        assert_eq!(SignalStrength::from_dbm(-67).centi_dbm(), -6700);
+        assert_eq!(SignalStrength::from_centi_dbm(-6700).dbm(), -67);
+        // Rounded down, -67.5 dBm being weaker than -67 dBm.
+        assert_eq!(SignalStrength::from_centi_dbm(-6750).dbm(), -68);
+        assert_eq!(SignalStrength::from_centi_dbm(-1).dbm(), -1);
+        assert_eq!(SignalStrength::from_dbm(i16::MIN).centi_dbm(), i16::MIN);
+        assert_eq!(SignalStrength::from_dbm(i16::MAX).centi_dbm(), i16::MAX);
+        assert_eq!(SignalStrength::from_centi_dbm(i16::MIN).dbm(), -328);
+        assert_eq!(SignalStrength::from_dbm(-67).to_string(), "-67 dBm");
+        assert!(SignalStrength::from_dbm(-50) > SignalStrength::from_centi_dbm(-5001));
+    }
+
+    #[test]
+    fn quality_is_clamped_to_the_scale() {
+        for (dbm, quality) in [(-120, 0), (-100, 0), (-70, 50), (-40, 100), (0, 100)] {
+            assert_eq!(
+                SignalStrength::from_dbm(dbm).quality(),
+                quality,
+                "{dbm} dBm"
+            );
+        }
+        assert_eq!(SignalStrength::from_dbm(i16::MIN).quality(), 0);
+        assert_eq!(SignalStrength::from_dbm(i16::MAX).quality(), 100);
+
+        let empty = QualityScale {
+            min_dbm: -60,
+            max_dbm: -60,
+        };
+        assert_eq!(SignalStrength::from_dbm(-60).quality_on(empty), 100);
+        assert_eq!(SignalStrength::from_dbm(-61).quality_on(empty), 0);
+    }
+
+    #[test]
+    fn bars_follow_iwctl_thresholds() {
+        for (centi_dbm, bars) in [
+            (-6000, 4),
+            (-6001, 3),
+            (-6700, 3),
+            (-6701, 2),
+            (-7500, 2),
+            (-7501, 1),
+            (-8500, 1),
+            (-8501, 0),
+            (i16::MIN, 0),
+        ] {
+            assert_eq!(
+                SignalStrength::from_centi_dbm(centi_dbm).bars(),
+                bars,
+                "{centi_dbm}"
+            );
+        }
+    }
+}
@@ -1,10 +1,10 @@
 use std::{cmp::Reverse, collections::HashMap, str::FromStr};
 
-use futures_lite::{Stream, StreamExt, stream};
+use futures_lite::{StreamExt, future};
 use strum::EnumString;
 use zvariant::{OwnedObjectPath, OwnedValue, Value};
 
-use zbus::{Connection, Proxy};
+use zbus::{Proxy, object_server::Interface};
 
 use crate::{
     error::{
@@ -13,47 +13,40 @@ use crate::{
         station::{DisconnectError, ScanError, StationDiagnosticsError},
     },
     hidden_network::HiddenNetwork,
-    iwd_interface::{IwdInterface, iwd_interface_impl},
-    network::{Network, NetworkType},
+    iwd_interface::{
+        IwdInterface, Properties, get_all, iwd_interface_impl, optional_property, property,
+    },
+    mac_address::MacAddress,
+    network::{Network, NetworkInfo, NetworkType},
+    session::managed_objects,
+    signal_strength::SignalStrength,
 };
 
 use signal_level_agent::SignalLevelAgentManager;
+use signal_level_stream::{SignalLevelBroadcaster, SignalLevelStream};
 pub mod diagnostics;
 pub mod signal_level_agent;
+pub mod signal_level_stream;
 
-iwd_interface_impl!(Station, "net.connman.iwd.Station");
+iwd_interface_impl!(Station, "net.connman.iwd.Station", {
+    State: State => state, state_stream;
+    Scanning: bool => is_scanning, scanning_stream;
+    optional ConnectedNetwork: OwnedObjectPath => connected_network_path, connected_network_path_stream;
+});
 
 impl Station {
-    pub async fn is_scanning(&self) -> zbus::Result<bool> {
-        let is_scanning: bool = self.proxy.get_property("Scanning").await?;
-        Ok(is_scanning)
-    }
-
-    pub async fn wait_for_scan_complete(&self) -> zbus::Result<()> {
-        let _ = crate::property_stream::<bool>(
-            self.proxy.clone(),
-            self.is_scanning().await,
-            "Scanning",
-        )
-        .await?
-        .skip_while(|scanning| scanning.as_ref().is_ok_and(|scanning| *scanning))
-        .next()
-        .await
-        .ok_or_else(|| zbus::Error::InvalidReply)??;
+    pub async fn wait_for_scan_complete(&self) -> crate::Result<()> {
+        let _ = self
+            .scanning_stream()
+            .await?
+            .skip_while(|scanning| scanning.as_ref().is_ok_and(|scanning| *scanning))
+            .next()
+            .await
+            .ok_or(crate::Error::ObjectVanished)??;
         Ok(())
     }
 
-    pub async fn state(&self) -> zbus::Result<State> {
-        self.proxy.get_property("State").await
-    }
-
-    pub async fn state_stream(
-        &self,
-    ) -> zbus::Result<impl Stream<Item = zbus::Result<State>> + Unpin + 'static> {
-        crate::property_stream(self.proxy.clone(), self.state().await, "State").await
-    }
-
-    pub async fn connected_network(&self) -> zbus::Result<Option<Network>> {
+    pub async fn connected_network(&self) -> crate::Result<Option<Network>> {
         let state = self.state().await?;
         if matches!(state, State::Connected) {
             let network_path: OwnedObjectPath = self.proxy.get_property("ConnectedNetwork").await?;
@@ -73,41 +66,76 @@ impl Station {
         Ok(())
     }
 
-    pub async fn discovered_networks(&self) -> zbus::Result<Vec<(Network, i16)>> {
+    pub async fn discovered_networks(&self) -> crate::Result<Vec<(Network, SignalStrength)>> {
+        let connection = self.proxy.connection();
+        let mut networks = Vec::new();
+        for (path, signal_strength) in self.ordered_networks().await? {
+            networks.push((
+                Network::new(connection.clone(), path).await?,
+                signal_strength,
+            ));
+        }
+        Ok(networks)
+    }
+
+    /// Same as [`Station::discovered_networks`] with the properties of every network, fetched in a single batch.
+    pub async fn discovered_networks_info(
+        &self,
+    ) -> crate::Result<Vec<(NetworkInfo, SignalStrength)>> {
+        let connection = self.proxy.connection();
+        let (networks, mut objects) =
+            future::try_zip(self.ordered_networks(), managed_objects(connection)).await?;
+
+        let mut infos = Vec::with_capacity(networks.len());
+        for (path, signal_strength) in networks {
+            let properties = objects
+                .get_mut(&path)
+                .and_then(|interfaces| interfaces.remove(Network::INTERFACE));
+            let network = Network::new(connection.clone(), path).await?;
+            let info = match properties {
+                Some(properties) => NetworkInfo::from_properties(network, &properties)?,
+                // The network appeared after the objects were fetched
+                None => network.info().await?,
+            };
+            infos.push((info, signal_strength));
+        }
+        Ok(infos)
+    }
+
+    async fn ordered_networks(&self) -> crate::Result<Vec<(OwnedObjectPath, SignalStrength)>> {
         let networks = self.proxy.call_method("GetOrderedNetworks", &()).await?;
 
         let body = networks.body();
-        let objects: Vec<(OwnedObjectPath, i16)> = body.deserialize()?;
-
-        let connection = self.proxy.connection().clone();
-        let networks = stream::iter(objects)
-            .then(|(path, signal_strength)| {
-                let connection = connection.clone();
-                async move {
-                    let network = Network::new(connection, path.clone()).await?;
-                    Ok::<_, zbus::Error>((network, signal_strength.to_owned()))
-                }
-            })
-            .try_collect()
-            .await?;
+        let networks: Vec<(OwnedObjectPath, i16)> = body.deserialize()?;
+        Ok(networks
+            .into_iter()
+            .map(|(path, signal_strength)| (path, SignalStrength::from_centi_dbm(signal_strength)))
+            .collect())
+    }
 
-        Ok(networks)
+    /// Fetch all the properties of the station in a single call.
+    pub async fn info(&self) -> crate::Result<StationInfo> {
+        StationInfo::from_properties(self.clone(), &get_all(&self.proxy).await?)
     }
 
-    pub async fn get_hidden_networks(&self) -> zbus::Result<Vec<HiddenNetwork>> {
+    pub async fn get_hidden_networks(&self) -> crate::Result<Vec<HiddenNetwork>> {
         let networks = self.proxy.call_method("GetHiddenAccessPoints", &()).await?;
 
         let body = networks.body();
         let networks: Vec<(String, i16, String)> = body.deserialize()?;
 
-        Ok(networks
+        networks
             .into_iter()
-            .map(|(address, signal_strength, network_type)| HiddenNetwork {
-                address,
-                signal_strength,
-                network_type: NetworkType::from_str(&network_type).unwrap(),
+            .map(|(address, signal_strength, network_type)| {
+                Ok(HiddenNetwork {
+                    address: MacAddress::from_str(&address)
+                        .map_err(|err| crate::Error::TypeMismatch(err.to_string()))?,
+                    signal_strength: SignalStrength::from_centi_dbm(signal_strength),
+                    network_type: NetworkType::from_str(&network_type)
+                        .unwrap_or(NetworkType::Unknown(network_type)),
+                })
             })
-            .collect())
+            .collect()
     }
 
     pub async fn connect_hidden_network(&self, ssid: String) -> IWDResult<(), ConnectError> {
@@ -125,11 +153,10 @@ impl Station {
     /// can be registered at any time.
     pub async fn register_signal_level_agent(
         &self,
-        mut levels: Vec<i16>,
+        levels: Vec<i16>,
         agent: impl signal_level_agent::SignalLevelAgent,
-    ) -> zbus::Result<SignalLevelAgentManager> {
-        // Signal level boundaries should be sorted
-        levels.sort_by_key(|signal_level| Reverse(*signal_level));
+    ) -> crate::Result<SignalLevelAgentManager> {
+        let levels = sorted_levels(levels);
 
         let interface = signal_level_agent::SignalLevelInterface {
             agent,
@@ -137,13 +164,80 @@ impl Station {
             levels: levels.clone(),
         };
 
-        let manager = SignalLevelAgentManager::register_agent(self.clone(), interface).await?;
+        self.register_signal_level_interface(levels, interface)
+            .await
+    }
+
+    /// Subscribe to signal strength level changes without implementing
+    /// [`crate::station::signal_level_agent::SignalLevelAgent`]. The "levels" parameter has the same meaning as in
+    /// [`Station::register_signal_level_agent`].
+    /// The returned stream can be cloned to share the single agent iwd allows per station between several
+    /// consumers, the agent gets unregistered once the last clone is dropped.
+    pub async fn signal_level_stream(&self, levels: Vec<i16>) -> crate::Result<SignalLevelStream> {
+        let levels = sorted_levels(levels);
 
-        self.proxy
-            .call_method("RegisterSignalLevelAgent", &(&manager.dbus_path, levels))
+        let (interface, receiver) = SignalLevelBroadcaster::new(levels.clone());
+        let current = interface.current();
+
+        let manager = self
+            .register_signal_level_interface(levels, interface)
+            .await?;
+        Ok(SignalLevelStream::new(receiver, manager, current))
+    }
+
+    async fn register_signal_level_interface(
+        &self,
+        levels: Vec<i16>,
+        interface: impl Interface,
+    ) -> crate::Result<SignalLevelAgentManager> {
+        let manager =
+            SignalLevelAgentManager::register_agent(self.clone(), interface, levels).await?;
+
+        self.register_signal_level_agent_path(&manager.dbus_path, &manager.levels)
             .await?;
         Ok(manager)
     }
+
+    /// Register an already served signal level agent object with iwd.
+    pub(crate) async fn register_signal_level_agent_path(
+        &self,
+        dbus_path: &OwnedObjectPath,
+        levels: &[i16],
+    ) -> crate::Result<()> {
+        self.proxy
+            .call_method("RegisterSignalLevelAgent", &(dbus_path, levels))
+            .await?;
+        Ok(())
+    }
+}
+
+fn sorted_levels(mut levels: Vec<i16>) -> Vec<i16> {
+    // Signal level boundaries should be sorted
+    levels.sort_by_key(|signal_level| Reverse(*signal_level));
+    levels
+}
+
+/// Snapshot of the properties of a [`Station`].
+#[derive(Debug, Clone)]
+pub struct StationInfo {
+    pub station: Station,
+    pub state: State,
+    pub scanning: bool,
+    pub connected_network: Option<OwnedObjectPath>,
+}
+
+impl StationInfo {
+    pub(crate) fn from_properties(
+        station: Station,
+        properties: &Properties,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            station,
+            state: property(properties, "State")?,
+            scanning: property(properties, "Scanning")?,
+            connected_network: optional_property(properties, "ConnectedNetwork")?,
+        })
+    }
 }
 
 iwd_interface_impl!(StationDiagnostics, "net.connman.iwd.StationDiagnostic");
@@ -157,24 +251,28 @@ impl StationDiagnostics {
         let body = diagnostic.body();
         let body: HashMap<String, Value> = body.deserialize()?;
 
-        Ok(diagnostics::ActiveStationDiagnostics::from_zbus_map(body)?)
+        diagnostics::ActiveStationDiagnostics::from_zbus_map(body)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, EnumString, strum::Display)]
+#[derive(Debug, Clone, PartialEq, Eq, EnumString, strum::Display)]
 #[strum(ascii_case_insensitive)]
+#[non_exhaustive]
 pub enum State {
     Connected,
     Disconnected,
     Connecting,
     Disconnecting,
     Roaming,
+    /// A state unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
 }
 
 impl TryFrom<OwnedValue> for State {
     type Error = zvariant::Error;
 
-    fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
+    fn try_from(value: OwnedValue) -> std::result::Result<Self, Self::Error> {
         let state_string: String = value.try_into()?;
         Self::from_str(&state_string).map_err(|_| zvariant::Error::IncorrectType)
     }
@@ -3,14 +3,32 @@ use std::{collections::HashMap, str::FromStr, time::Duration};
 use strum::EnumString;
 use zvariant::Value;
 
+use crate::{
+    mac_address::MacAddress,
+    radio::{Band, Channel},
+    signal_strength::SignalStrength,
+};
+
+/// Decode the optional entry `$key` of a diagnostics dictionary.
+macro_rules! some_try_into {
+    ($map:ident, $key:expr) => {
+        match $map.get($key) {
+            Some(value) => Some(value.try_into()?),
+            None => None,
+        }
+    };
+}
+
+pub(crate) use some_try_into;
+
 #[derive(Debug, Clone)]
 pub struct ActiveStationDiagnostics {
-    pub connected_bss: String,
+    pub connected_bss: MacAddress,
     pub frequency_mhz: u32,
     pub channel: Option<u16>,
     pub security: StationSecurity,
-    pub rssi: Option<i16>,
-    pub average_rssi: Option<i16>,
+    pub rssi: Option<SignalStrength>,
+    pub average_rssi: Option<SignalStrength>,
     pub rx_mode: Option<Mode>,
     pub rx_rate_kbps: Option<u64>,
     pub rx_mcs: Option<u8>,
@@ -23,32 +41,27 @@ pub struct ActiveStationDiagnostics {
 }
 
 impl ActiveStationDiagnostics {
-    pub(crate) fn from_zbus_map(body: HashMap<String, Value>) -> zbus::Result<Self> {
-        macro_rules! some_try_into {
-            ($map:ident, $key:expr) => {
-                match $map.get($key) {
-                    Some(value) => Some(value.try_into()?),
-                    None => None,
-                }
-            };
-        }
+    pub(crate) fn from_zbus_map<E>(body: HashMap<String, Value>) -> crate::Result<Self, E> {
+        let frequency_mhz = body
+            .get("Frequency")
+            .ok_or_else(|| crate::Error::missing_property("Frequency"))?
+            .try_into()?;
 
         Ok(Self {
             connected_bss: body
                 .get("ConnectedBss")
-                .ok_or(zbus::Error::MissingField)?
+                .ok_or_else(|| crate::Error::missing_property("ConnectedBss"))?
                 .try_into()?,
-            frequency_mhz: body
-                .get("Frequency")
-                .ok_or(zbus::Error::MissingField)?
-                .try_into()?,
-            channel: some_try_into!(body, "Channel"),
+            frequency_mhz,
+            // Older iwd versions only report the frequency
+            channel: some_try_into!(body, "Channel")
+                .or_else(|| Channel::from_frequency(frequency_mhz).map(|channel| channel.number)),
             security: body
                 .get("Security")
-                .ok_or(zbus::Error::MissingField)?
+                .ok_or_else(|| crate::Error::missing_property("Security"))?
                 .try_into()?,
-            rssi: some_try_into!(body, "RSSI"),
-            average_rssi: some_try_into!(body, "AverageRSSI"),
+            rssi: some_try_into!(body, "RSSI").map(SignalStrength::from_dbm),
+            average_rssi: some_try_into!(body, "AverageRSSI").map(SignalStrength::from_dbm),
             rx_mode: some_try_into!(body, "RxMode"),
             rx_rate_kbps: some_try_into!(body, "RxBitrate")
                 .map(|rate_100_kpbs: u32| 100 * u64::from(rate_100_kpbs)),
@@ -56,7 +69,7 @@ impl ActiveStationDiagnostics {
             tx_mode: some_try_into!(body, "TxMode"),
             tx_rate_kbps: some_try_into!(body, "TxBitrate")
                 .map(|rate_100_kpbs: u32| 100 * u64::from(rate_100_kpbs)),
-            tx_mcs: some_try_into!(body, "RxMCS"),
+            tx_mcs: some_try_into!(body, "TxMCS"),
             pairwise_cipher: some_try_into!(body, "PairwiseCipher"),
             inactive_time: some_try_into!(body, "InactiveTime")
                 .map(|inactive_time_ms: u32| Duration::from_millis(u64::from(inactive_time_ms))),
@@ -64,6 +77,10 @@ impl ActiveStationDiagnostics {
                 .map(|connected_time_s: u32| Duration::from_secs(u64::from(connected_time_s))),
         })
     }
+
+    pub fn band(&self) -> Option<Band> {
+        Band::from_frequency(self.frequency_mhz)
+    }
 }
 
 macro_rules! enum_from_zbus_string_value {
@@ -81,7 +98,8 @@ macro_rules! enum_from_zbus_string_value {
     };
 }
 
-#[derive(Debug, EnumString, strum::Display, Clone)]
+#[derive(Debug, EnumString, strum::Display, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum StationSecurity {
     // Options from
     // https://git.kernel.org/pub/scm/network/wireless/iwd.git/tree/src/diagnostic.c#n124
@@ -108,24 +126,39 @@ pub enum StationSecurity {
     FilsFt,
     #[strum(serialize = "OSEN")]
     Osen,
-    Unknown,
+    /// A security unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
 }
 
 enum_from_zbus_string_value!(StationSecurity);
 
-#[derive(Debug, EnumString, strum::Display, Clone)]
+#[derive(Debug, EnumString, strum::Display, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Mode {
+    #[strum(serialize = "802.11a")]
+    A,
+    #[strum(serialize = "802.11b")]
+    B,
+    #[strum(serialize = "802.11g")]
+    G,
     #[strum(serialize = "802.11n")]
     N,
     #[strum(serialize = "802.11ac")]
     AC,
     #[strum(serialize = "802.11ax")]
     AX,
+    #[strum(serialize = "802.11be")]
+    BE,
+    /// A mode unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
 }
 
 enum_from_zbus_string_value!(Mode);
 
-#[derive(Debug, EnumString, strum::Display, Clone)]
+#[derive(Debug, EnumString, strum::Display, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum PairwiseCipher {
     #[strum(serialize = "TKIP")]
     Tkip,
@@ -137,6 +170,37 @@ pub enum PairwiseCipher {
     Gcmp128,
     #[strum(serialize = "GCMP-256")]
     Gcmp256,
+    /// A cipher unknown to this version of the crate.
+    #[strum(default)]
+    Unknown(String),
 }
 
 enum_from_zbus_string_value!(PairwiseCipher);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zvariant::Value;
+
+    use super::{ActiveStationDiagnostics, StationSecurity};
+    use crate::error::NoOperationError;
+
+    #[test]
+    fn decodes_rx_and_tx_mcs_separately() {
+        let body = HashMap::from([
+            ("ConnectedBss".to_string(), Value::from("02:00:00:00:01:00")),
+            ("Frequency".to_string(), Value::from(5180u32)),
+            ("Security".to_string(), Value::from("WPA2-Personal")),
+            ("RxMCS".to_string(), Value::from(9u8)),
+            ("TxMCS".to_string(), Value::from(7u8)),
+        ]);
+
+        let diagnostics =
+            ActiveStationDiagnostics::from_zbus_map::<NoOperationError>(body).unwrap();
+        assert_eq!(diagnostics.security, StationSecurity::WPA2Personal);
+        assert_eq!(diagnostics.channel, Some(36));
+        assert_eq!(diagnostics.rx_mcs, Some(9));
+        assert_eq!(diagnostics.tx_mcs, Some(7));
+    }
+}
@@ -1,10 +1,12 @@
 use std::ops::{Bound, RangeBounds};
 
 use uuid::Uuid;
-use zbus::{Connection, interface};
+use zbus::{Connection, interface, object_server::Interface};
 use zvariant::OwnedObjectPath;
 
-use crate::{iwd_interface::IwdInterface, station::Station};
+use crate::{
+    Error, iwd_interface::IwdInterface, signal_strength::SignalStrength, station::Station,
+};
 
 pub trait SignalLevelAgent: Send + Sync + 'static {
     /// This method gets called when the service daemon unregisters the agent. An agent can use it to do
@@ -38,36 +40,75 @@ impl<A: SignalLevelAgent> SignalLevelInterface<A> {
     /// signal meter.
     #[zbus(name = "Changed")]
     async fn changed(&self, station_path: OwnedObjectPath, level_idx: u8) -> zbus::fdo::Result<()> {
-        let station = Station::new(self.connection.clone(), station_path).await?;
-
-        let level_idx = usize::from(level_idx);
-
-        let max_strength = level_idx
-            .checked_sub(1)
-            .and_then(|level| self.levels.get(level))
-            .map(|level| Bound::Excluded(*level))
-            .unwrap_or(Bound::Unbounded);
-        let min_strength = self
-            .levels
-            .get(level_idx)
-            .map(|level| Bound::Included(*level))
-            .unwrap_or(Bound::Unbounded);
-
-        self.agent.changed(&station, (min_strength, max_strength));
+        let station = Station::new(self.connection.clone(), station_path)
+            .await
+            .map_err(Error::into_fdo)?;
+
+        self.agent
+            .changed(&station, SignalLevel::new(&self.levels, level_idx));
         Ok(())
     }
 }
 
+/// One of the N+1 signal strength ranges delimited by the N thresholds (in dBm) passed when registering for
+/// signal level notifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalLevel {
+    /// Index of the range, 0 being the strongest signal.
+    pub index: u8,
+    /// Lower bound (inclusive) of the range in dBm, `None` for the weakest range.
+    pub min_dbm: Option<i16>,
+    /// Upper bound (exclusive) of the range in dBm, `None` for the strongest range.
+    pub max_dbm: Option<i16>,
+}
+
+impl SignalLevel {
+    /// `levels` must be sorted from the strongest to the weakest threshold.
+    pub(crate) fn new(levels: &[i16], index: u8) -> Self {
+        let level_idx = usize::from(index);
+
+        Self {
+            index,
+            min_dbm: levels.get(level_idx).copied(),
+            max_dbm: level_idx
+                .checked_sub(1)
+                .and_then(|level| levels.get(level))
+                .copied(),
+        }
+    }
+
+    /// Whether the signal strength falls within this level.
+    pub fn contains_strength(&self, strength: SignalStrength) -> bool {
+        self.contains(&strength.dbm())
+    }
+}
+
+impl RangeBounds<i16> for SignalLevel {
+    fn start_bound(&self) -> Bound<&i16> {
+        self.min_dbm
+            .as_ref()
+            .map_or(Bound::Unbounded, Bound::Included)
+    }
+
+    fn end_bound(&self) -> Bound<&i16> {
+        self.max_dbm
+            .as_ref()
+            .map_or(Bound::Unbounded, Bound::Excluded)
+    }
+}
+
 pub struct SignalLevelAgentManager {
     pub(crate) dbus_path: OwnedObjectPath,
     pub(crate) station: super::Station,
+    pub(crate) levels: Vec<i16>,
 }
 
 impl SignalLevelAgentManager {
     pub(crate) async fn register_agent(
         station: super::Station,
-        interface: SignalLevelInterface<impl SignalLevelAgent>,
-    ) -> zbus::Result<Self> {
+        interface: impl Interface,
+        levels: Vec<i16>,
+    ) -> crate::Result<Self> {
         let dbus_path = OwnedObjectPath::try_from(format!(
             "/iwdrs/signal_level_agent/{}",
             Uuid::new_v4().as_simple()
@@ -79,10 +120,14 @@ impl SignalLevelAgentManager {
             .at(dbus_path.clone(), interface)
             .await?;
 
-        Ok(Self { dbus_path, station })
+        Ok(Self {
+            dbus_path,
+            station,
+            levels,
+        })
     }
 
-    pub async fn unregister(self) -> zbus::Result<()> {
+    pub async fn unregister(self) -> crate::Result<()> {
         self.station
             .proxy
             .call_method("UnregisterSignalLevelAgent", &(&self.dbus_path,))
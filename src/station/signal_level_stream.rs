@@ -0,0 +1,130 @@
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll},
+};
+
+use async_broadcast::{Receiver, Sender};
+use futures_lite::Stream;
+use zbus::interface;
+use zvariant::OwnedObjectPath;
+
+use crate::station::signal_level_agent::{SignalLevel, SignalLevelAgentManager};
+
+// Slow subscribers only ever care about the latest levels, older ones get dropped once the channel is full.
+const CHANNEL_CAPACITY: usize = 8;
+
+pub(super) struct SignalLevelBroadcaster {
+    levels: Vec<i16>,
+    sender: Sender<SignalLevel>,
+    current: Arc<Mutex<Option<SignalLevel>>>,
+}
+
+impl SignalLevelBroadcaster {
+    pub(super) fn new(levels: Vec<i16>) -> (Self, Receiver<SignalLevel>) {
+        let (mut sender, receiver) = async_broadcast::broadcast(CHANNEL_CAPACITY);
+        sender.set_overflow(true);
+
+        let broadcaster = Self {
+            levels,
+            sender,
+            current: Arc::default(),
+        };
+        (broadcaster, receiver)
+    }
+
+    pub(super) fn current(&self) -> Arc<Mutex<Option<SignalLevel>>> {
+        self.current.clone()
+    }
+}
+
+#[interface(name = "net.connman.iwd.SignalLevelAgent")]
+impl SignalLevelBroadcaster {
+    #[zbus(name = "Release")]
+    fn release(&self) {
+        self.sender.close();
+    }
+
+    #[zbus(name = "Changed")]
+    fn changed(&self, _station_path: OwnedObjectPath, level_idx: u8) {
+        let level = SignalLevel::new(&self.levels, level_idx);
+        // A plain value, which a panicking holder of the lock can not leave inconsistent.
+        *self.current.lock().unwrap_or_else(PoisonError::into_inner) = Some(level);
+
+        // Only fails when every subscriber is gone, in which case the agent is being unregistered anyway.
+        let _ = self.sender.try_broadcast(level);
+    }
+}
+
+/// A subscription to the signal level changes of a station.
+///
+/// All the clones of a stream share the same signal level agent, which gets unregistered from iwd once the last
+/// clone is dropped. The stream ends if iwd releases the agent.
+#[derive(Clone)]
+pub struct SignalLevelStream {
+    receiver: Receiver<SignalLevel>,
+    registration: Arc<Registration>,
+}
+
+impl SignalLevelStream {
+    pub(super) fn new(
+        receiver: Receiver<SignalLevel>,
+        manager: SignalLevelAgentManager,
+        current: Arc<Mutex<Option<SignalLevel>>>,
+    ) -> Self {
+        Self {
+            receiver,
+            registration: Arc::new(Registration {
+                manager: Some(manager),
+                current,
+            }),
+        }
+    }
+
+    /// The last signal level reported by iwd, if any.
+    pub fn current(&self) -> Option<SignalLevel> {
+        *self
+            .registration
+            .current
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Stream for SignalLevelStream {
+    type Item = SignalLevel;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+struct Registration {
+    manager: Option<SignalLevelAgentManager>,
+    current: Arc<Mutex<Option<SignalLevel>>>,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let Some(manager) = self.manager.take() else {
+            return;
+        };
+        let connection = manager.station.proxy.connection().clone();
+        connection
+            .executor()
+            .spawn(
+                async move {
+                    let connection = manager.station.proxy.connection().clone();
+                    let dbus_path = manager.dbus_path.clone();
+                    // iwd might already be gone, nothing left to clean up on its side in that case.
+                    let _ = manager.unregister().await;
+                    let _ = connection
+                        .object_server()
+                        .remove::<SignalLevelBroadcaster, _>(dbus_path)
+                        .await;
+                },
+                "iwdrs-unregister-signal-level-agent",
+            )
+            .detach();
+    }
+}
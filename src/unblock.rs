@@ -0,0 +1,13 @@
+/// Run `f`, which blocks on file system I/O such as sysfs reads, on a thread pool instead of the executor.
+#[cfg(feature = "tokio")]
+pub(crate) async fn unblock<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    match tokio::task::spawn_blocking(f).await {
+        Ok(value) => value,
+        Err(err) => std::panic::resume_unwind(err.into_panic()),
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+pub(crate) async fn unblock<T: Send + 'static>(f: impl FnOnce() -> T + Send + 'static) -> T {
+    blocking::unblock(f).await
+}
@@ -0,0 +1,740 @@
+//! Wi-Fi credentials in the `WIFI:` format understood by phone cameras when encoded in a QR code.
+
+use std::{
+    fmt::{Display, Write as _},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use crate::{
+    Error,
+    agent::Agent,
+    error::{agent::Canceled, network::ConnectError},
+    network::{Network, NetworkType},
+    session::Session,
+    settings::{escape_value, is_encodable, write_secret_file},
+    station::Station,
+};
+
+/// Credentials of a network, formatted as `WIFI:T:WPA;S:<ssid>;P:<passphrase>;;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiQr {
+    pub ssid: String,
+    pub authentication: WifiAuthentication,
+    pub password: Option<String>,
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WifiAuthentication {
+    Open,
+    Wep,
+    /// WPA, WPA2 or WPA3 personal.
+    Wpa,
+    /// WPA2 enterprise, the password of [`WifiQr`] being the one of the identity.
+    Wpa2Eap(EapCredentials),
+}
+
+/// The `WPA2-EAP` extension fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EapCredentials {
+    /// `E`, e.g. `PEAP`, `TTLS` or `PWD`.
+    pub method: Option<String>,
+    /// `A`, the identity sent outside of the tunnel.
+    pub anonymous_identity: Option<String>,
+    /// `I`
+    pub identity: Option<String>,
+    /// `PH2`, e.g. `MSCHAPV2`.
+    pub phase2_method: Option<String>,
+}
+
+impl WifiAuthentication {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Open => "nopass",
+            Self::Wep => "WEP",
+            Self::Wpa => "WPA",
+            Self::Wpa2Eap(_) => "WPA2-EAP",
+        }
+    }
+
+    pub fn network_type(&self) -> NetworkType {
+        match self {
+            Self::Open => NetworkType::Open,
+            Self::Wep => NetworkType::Wep,
+            Self::Wpa => NetworkType::Psk,
+            Self::Wpa2Eap(_) => NetworkType::Eap,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid WIFI: QR code payload: {0}")]
+pub struct ParseWifiQrError(&'static str);
+
+impl WifiQr {
+    /// The bytes of the SSID if it reads as hex encoded, which some generators do for SSIDs that are not UTF-8.
+    /// The format does not tell a hex encoded SSID from an SSID made of hexadecimal digits, so which one the
+    /// network uses is up to the caller, e.g. by matching the scan results.
+    pub fn hex_ssid(&self) -> Option<Vec<u8>> {
+        if self.ssid.is_empty()
+            || !self.ssid.len().is_multiple_of(2)
+            || !self.ssid.bytes().all(|byte| byte.is_ascii_hexdigit())
+        {
+            return None;
+        }
+        (0..self.ssid.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&self.ssid[i..i + 2], 16).ok())
+            .collect()
+    }
+
+    /// The SSID as broadcast by the network: the [`WifiQr::hex_ssid`] bytes when they are not UTF-8, which only a
+    /// hex encoded SSID produces, the SSID as written otherwise.
+    pub fn ssid_bytes(&self) -> Vec<u8> {
+        match self.hex_ssid() {
+            Some(bytes) if std::str::from_utf8(&bytes).is_err() => bytes,
+            _ => self.ssid.clone().into_bytes(),
+        }
+    }
+
+    /// Name of the iwd network profile, `<ssid>.<type>` or `=<hex ssid>.<type>` when the SSID of
+    /// [`WifiQr::ssid_bytes`] contains characters other than alphanumerics, `-`, `_` and spaces.
+    pub fn profile_name(&self) -> String {
+        let extension = match &self.authentication {
+            WifiAuthentication::Open => "open",
+            WifiAuthentication::Wep => "wep",
+            WifiAuthentication::Wpa => "psk",
+            WifiAuthentication::Wpa2Eap(_) => "8021x",
+        };
+
+        let ssid = self.ssid_bytes();
+        match std::str::from_utf8(&ssid) {
+            Ok(ssid)
+                if ssid
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' ')) =>
+            {
+                format!("{ssid}.{extension}")
+            }
+            _ => {
+                let mut hex_ssid = String::with_capacity(2 * ssid.len());
+                for byte in ssid {
+                    let _ = write!(hex_ssid, "{byte:02x}");
+                }
+                format!("={hex_ssid}.{extension}")
+            }
+        }
+    }
+
+    /// Content of the iwd network profile, see `iwd.network(5)`. Enterprise networks are limited to the `PEAP`,
+    /// `TTLS` and `PWD` methods, which only need the credentials of the QR code.
+    pub fn profile(&self) -> crate::Result<String> {
+        if !(1..=32).contains(&self.ssid_bytes().len()) {
+            return Err(Error::InvalidArgument(
+                "the SSID must be 1 to 32 bytes long".to_string(),
+            ));
+        }
+
+        let eap = match &self.authentication {
+            WifiAuthentication::Wpa2Eap(eap) => Some(eap),
+            _ => None,
+        };
+        let values = [
+            self.password.as_ref(),
+            eap.and_then(|eap| eap.anonymous_identity.as_ref()),
+            eap.and_then(|eap| eap.identity.as_ref()),
+            eap.and_then(|eap| eap.phase2_method.as_ref()),
+        ];
+        if !values
+            .into_iter()
+            .flatten()
+            .all(|value| is_encodable(value))
+        {
+            return Err(Error::InvalidArgument(
+                "the credentials can not contain control characters".to_string(),
+            ));
+        }
+
+        let mut profile = String::new();
+        match &self.authentication {
+            WifiAuthentication::Open => {}
+            WifiAuthentication::Wep => {
+                return Err(Error::InvalidArgument(
+                    "iwd does not support WEP".to_string(),
+                ));
+            }
+            WifiAuthentication::Wpa => {
+                let password = self.password.as_deref().ok_or_else(|| {
+                    Error::InvalidArgument("a WPA network requires a password".to_string())
+                })?;
+                profile.push_str(&format!(
+                    "[Security]\nPassphrase={}\n\n",
+                    escape_value(password)
+                ));
+            }
+            WifiAuthentication::Wpa2Eap(eap) => {
+                let method = eap.method.as_deref().unwrap_or("PEAP").to_uppercase();
+                let mut security = format!("[Security]\nEAP-Method={method}\n");
+                let mut push = |key: String, value: &str| {
+                    security.push_str(&format!("{key}={}\n", escape_value(value)));
+                };
+                match method.as_str() {
+                    "PEAP" | "TTLS" => {
+                        if let Some(anonymous_identity) = &eap.anonymous_identity {
+                            push("EAP-Identity".to_string(), anonymous_identity);
+                        }
+                        let phase2_method = phase2_method(&method, eap.phase2_method.as_deref())?;
+                        push(format!("EAP-{method}-Phase2-Method"), phase2_method);
+                        if let Some(identity) = &eap.identity {
+                            push(format!("EAP-{method}-Phase2-Identity"), identity);
+                        }
+                        if let Some(password) = &self.password {
+                            push(format!("EAP-{method}-Phase2-Password"), password);
+                        }
+                    }
+                    "PWD" => {
+                        if let Some(identity) = &eap.identity {
+                            push("EAP-PWD-Identity".to_string(), identity);
+                        }
+                        if let Some(password) = &self.password {
+                            push("EAP-PWD-Password".to_string(), password);
+                        }
+                    }
+                    _ => {
+                        return Err(Error::InvalidArgument(format!(
+                            "the {method} EAP method is not supported"
+                        )));
+                    }
+                }
+                profile.push_str(&security);
+                profile.push('\n');
+            }
+        }
+
+        if self.hidden {
+            profile.push_str("[Settings]\nHidden=true\n");
+        } else if profile.ends_with("\n\n") {
+            profile.pop();
+        }
+        Ok(profile)
+    }
+
+    /// Write the network profile, readable by its owner only, into iwd's state directory. iwd picks it up and
+    /// connects to the network once in range.
+    pub fn write_profile(&self, state_dir: &Path) -> crate::Result<PathBuf> {
+        let profile = self.profile()?;
+
+        let path = state_dir.join(self.profile_name());
+        write_secret_file(&path, &profile)?;
+        Ok(path)
+    }
+
+    /// Same as [`WifiQr::write_profile`], in the state directory reported by iwd.
+    pub async fn provision(&self, session: &Session) -> crate::Result<PathBuf> {
+        let state_dir = session.daemon().await?.get_info().await?.state_dir;
+        self.write_profile(&state_dir)
+    }
+
+    /// Connect the station to the network, answering iwd's credential requests from the QR code.
+    ///
+    /// Hidden networks are connected through [`Station::connect_hidden_network`], other networks must show up in
+    /// the scan results.
+    pub async fn connect(
+        &self,
+        session: &Session,
+        station: &Station,
+    ) -> crate::Result<(), ConnectError> {
+        let agent = QrAgent {
+            identity: match &self.authentication {
+                WifiAuthentication::Wpa2Eap(eap) => eap.identity.clone(),
+                _ => None,
+            },
+            password: self.password.clone(),
+        };
+        let agent_manager = session
+            .register_agent(agent)
+            .await
+            .map_err(Error::into_operation)?;
+
+        let connected = self.connect_with_agent(station).await;
+        let _ = agent_manager.unregister_agent::<QrAgent>().await;
+        connected
+    }
+
+    async fn connect_with_agent(&self, station: &Station) -> crate::Result<(), ConnectError> {
+        if self.hidden {
+            return station.connect_hidden_network(self.ssid.clone()).await;
+        }
+
+        let network = match self.find_network(station).await? {
+            Some(network) => network,
+            None => {
+                let _ = station.scan().await;
+                station
+                    .wait_for_scan_complete()
+                    .await
+                    .map_err(Error::into_operation)?;
+                self.find_network(station).await?.ok_or_else(|| {
+                    Error::InvalidArgument(format!("{} is not in range", self.ssid))
+                })?
+            }
+        };
+        network.connect().await
+    }
+
+    async fn find_network(
+        &self,
+        station: &Station,
+    ) -> crate::Result<Option<Network>, ConnectError> {
+        let network_type = self.authentication.network_type();
+        let networks = station
+            .discovered_networks()
+            .await
+            .map_err(Error::into_operation)?
+            .into_iter()
+            .filter_map(|(network, _)| {
+                let id = network.id()?;
+                (id.network_type == network_type).then_some((network, id.ssid_bytes))
+            })
+            .collect::<Vec<_>>();
+
+        // The SSID as written wins over its hex interpretation, the format not telling them apart.
+        Ok(std::iter::once(self.ssid.as_bytes().to_vec())
+            .chain(self.hex_ssid())
+            .find_map(|ssid| {
+                networks
+                    .iter()
+                    .find(|(_, ssid_bytes)| *ssid_bytes == ssid)
+                    .map(|(network, _)| network.clone())
+            }))
+    }
+
+    /// Render the QR code with unicode half blocks, light on dark as expected by most terminals.
+    #[cfg(feature = "qrcode")]
+    pub fn to_terminal_string(&self) -> crate::Result<String> {
+        use qrcode::render::unicode::Dense1x2;
+
+        Ok(self
+            .qr_code()?
+            .render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .build())
+    }
+
+    /// Render the QR code as an SVG document.
+    #[cfg(feature = "qrcode")]
+    pub fn to_svg(&self) -> crate::Result<String> {
+        use qrcode::render::svg;
+
+        Ok(self
+            .qr_code()?
+            .render::<svg::Color>()
+            .min_dimensions(256, 256)
+            .build())
+    }
+
+    #[cfg(feature = "qrcode")]
+    fn qr_code(&self) -> crate::Result<qrcode::QrCode> {
+        qrcode::QrCode::new(self.to_string())
+            .map_err(|err| crate::Error::InvalidArgument(err.to_string()))
+    }
+}
+
+impl Display for WifiQr {
+    /// The `WIFI:` payload of the QR code.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ssid = escape(&self.ssid);
+        // Quoted so that readers do not decode it as hex.
+        if !self.ssid.is_empty() && self.ssid.chars().all(|c| c.is_ascii_hexdigit()) {
+            write!(f, "WIFI:T:{};S:\"{ssid}\";", self.authentication.code())?;
+        } else {
+            write!(f, "WIFI:T:{};S:{ssid};", self.authentication.code())?;
+        }
+        if let Some(password) = &self.password {
+            write!(f, "P:{};", escape(password))?;
+        }
+        if let WifiAuthentication::Wpa2Eap(eap) = &self.authentication {
+            for (key, value) in [
+                ("E", &eap.method),
+                ("A", &eap.anonymous_identity),
+                ("I", &eap.identity),
+                ("PH2", &eap.phase2_method),
+            ] {
+                if let Some(value) = value {
+                    write!(f, "{key}:{};", escape(value))?;
+                }
+            }
+        }
+        if self.hidden {
+            write!(f, "H:true;")?;
+        }
+        write!(f, ";")
+    }
+}
+
+impl FromStr for WifiQr {
+    type Err = ParseWifiQrError;
+
+    /// Parse the ZXing `WIFI:` format. Fields can appear in any order and unknown fields are ignored. The SSID is
+    /// kept as written, see [`WifiQr::hex_ssid`] for the ones which may be hex encoded.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s
+            .strip_prefix("WIFI:")
+            .or_else(|| s.strip_prefix("wifi:"))
+            .ok_or(ParseWifiQrError("missing the WIFI: prefix"))?;
+
+        let mut authentication = None;
+        let mut ssid = None;
+        let mut password = None;
+        let mut hidden = false;
+        let mut eap = EapCredentials::default();
+
+        for field in split_fields(fields) {
+            if field.is_empty() {
+                break;
+            }
+            let (key, value) = field
+                .split_once(':')
+                .ok_or(ParseWifiQrError("field without a key"))?;
+            match key {
+                "T" => authentication = Some(value.to_string()),
+                "S" => ssid = Some(unquote(value)),
+                "P" => password = Some(unquote(value)),
+                "H" => hidden = value.eq_ignore_ascii_case("true"),
+                "E" => eap.method = Some(unquote(value)),
+                "A" => eap.anonymous_identity = Some(unquote(value)),
+                "I" => eap.identity = Some(unquote(value)),
+                "PH2" => eap.phase2_method = Some(unquote(value)),
+                _ => {}
+            }
+        }
+
+        let ssid = ssid.ok_or(ParseWifiQrError("missing the SSID"))?;
+        let authentication = match authentication.as_deref() {
+            None | Some("") | Some("nopass") => match password {
+                Some(_) => WifiAuthentication::Wpa,
+                None => WifiAuthentication::Open,
+            },
+            Some(kind) if kind.eq_ignore_ascii_case("WEP") => WifiAuthentication::Wep,
+            Some(kind) if kind.eq_ignore_ascii_case("WPA") || kind.eq_ignore_ascii_case("SAE") => {
+                WifiAuthentication::Wpa
+            }
+            Some(kind) if kind.eq_ignore_ascii_case("WPA2-EAP") => WifiAuthentication::Wpa2Eap(eap),
+            Some(_) => return Err(ParseWifiQrError("unknown authentication type")),
+        };
+
+        Ok(Self {
+            ssid,
+            authentication,
+            password,
+            hidden,
+        })
+    }
+}
+
+/// iwd's name of the `PH2` method inside a `PEAP` or `TTLS` tunnel, `MSCHAPV2` by default. Like Android, the QR code
+/// names the non-EAP `TTLS` methods after their EAP counterparts, which iwd prefixes with `Tunneled-`.
+fn phase2_method(method: &str, phase2_method: Option<&str>) -> crate::Result<&'static str> {
+    let phase2_method = phase2_method.unwrap_or("MSCHAPV2").to_uppercase();
+    match (method, phase2_method.as_str()) {
+        ("TTLS", "PAP") => Ok("Tunneled-PAP"),
+        ("TTLS", "CHAP") => Ok("Tunneled-CHAP"),
+        ("TTLS", "MSCHAP") => Ok("Tunneled-MSCHAP"),
+        ("TTLS", "MSCHAPV2") => Ok("Tunneled-MSCHAPv2"),
+        ("PEAP", "MSCHAPV2") => Ok("MSCHAPV2"),
+        (_, "GTC") => Ok("GTC"),
+        (_, "MD5") => Ok("MD5"),
+        _ => Err(Error::InvalidArgument(format!(
+            "the {phase2_method} inner method is not supported with {method}"
+        ))),
+    }
+}
+
+/// Split at the `;` which are not escaped, keeping the escapes for [`unquote`].
+fn split_fields(fields: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in fields.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' => {
+                split.push(&fields[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < fields.len() {
+        split.push(&fields[start..]);
+    }
+    split
+}
+
+/// Remove the escapes, and the surrounding double quotes if any.
+fn unquote(value: &str) -> String {
+    let value = match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(inner) if !inner.ends_with('\\') || inner.ends_with("\\\\") => inner,
+        _ => value,
+    };
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Backslash escape the characters delimiting the fields.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ';' | ',' | ':' | '"') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Answers iwd's credential requests with the ones of the QR code.
+struct QrAgent {
+    identity: Option<String>,
+    password: Option<String>,
+}
+
+impl QrAgent {
+    fn password(&self) -> Result<String, Canceled> {
+        self.password.clone().ok_or(Canceled())
+    }
+}
+
+impl Agent for QrAgent {
+    async fn request_passphrase(&self, _network: &Network) -> Result<String, Canceled> {
+        self.password()
+    }
+
+    async fn request_private_key_passphrase(&self, _network: &Network) -> Result<String, Canceled> {
+        Err(Canceled())
+    }
+
+    async fn request_user_name_and_passphrase(
+        &self,
+        _network: &Network,
+    ) -> Result<(String, String), Canceled> {
+        Ok((self.identity.clone().ok_or(Canceled())?, self.password()?))
+    }
+
+    async fn request_user_password(
+        &self,
+        _network: &Network,
+        _user_name: Option<&String>,
+    ) -> Result<String, Canceled> {
+        self.password()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::{EapCredentials, WifiAuthentication, WifiQr};
+
+    fn wpa(ssid: &str, password: &str) -> WifiQr {
+        WifiQr {
+            ssid: ssid.to_string(),
+            authentication: WifiAuthentication::Wpa,
+            password: Some(password.to_string()),
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn parses_fields_in_any_order() {
+        assert_eq!(
+            WifiQr::from_str("WIFI:T:WPA;S:MyNet;P:secret;;"),
+            Ok(wpa("MyNet", "secret"))
+        );
+        assert_eq!(
+            WifiQr::from_str("WIFI:H:true;P:secret;X:ignored;S:MyNet;T:WPA;;"),
+            Ok(WifiQr {
+                hidden: true,
+                ..wpa("MyNet", "secret")
+            })
+        );
+        // Nothing is read past the `;;` terminator.
+        assert_eq!(
+            WifiQr::from_str("WIFI:S:MyNet;;T:WEP;P:secret;"),
+            Ok(WifiQr {
+                ssid: "MyNet".to_string(),
+                authentication: WifiAuthentication::Open,
+                password: None,
+                hidden: false,
+            })
+        );
+        assert!(WifiQr::from_str("T:WPA;S:MyNet;;").is_err());
+        assert!(WifiQr::from_str("WIFI:T:WPA;P:secret;;").is_err());
+        assert!(WifiQr::from_str("WIFI:T:WPA4;S:MyNet;;").is_err());
+    }
+
+    #[test]
+    fn unescapes_and_unquotes() {
+        assert_eq!(
+            WifiQr::from_str(r#"WIFI:T:WPA;S:My\;Net\:\,;P:back\\slash\";;"#),
+            Ok(wpa("My;Net:,", "back\\slash\""))
+        );
+        assert_eq!(
+            WifiQr::from_str(r#"WIFI:T:WPA;S:"quoted";P:"semi\;colon";;"#),
+            Ok(wpa("quoted", "semi;colon"))
+        );
+    }
+
+    #[test]
+    fn keeps_hex_looking_ssids() {
+        let qr = WifiQr::from_str("WIFI:T:WPA;S:2020;P:secret;;").unwrap();
+        assert_eq!(qr.ssid, "2020");
+        assert_eq!(qr.hex_ssid(), Some(b"  ".to_vec()));
+
+        let qr = WifiQr::from_str(r#"WIFI:T:WPA;S:"4142";P:secret;;"#).unwrap();
+        assert_eq!(qr.ssid, "4142");
+        assert_eq!(qr.hex_ssid(), Some(b"AB".to_vec()));
+
+        assert_eq!(wpa("414", "secret").hex_ssid(), None);
+        assert_eq!(wpa("+1+1", "secret").hex_ssid(), None);
+        assert_eq!(wpa("MyNet", "secret").hex_ssid(), None);
+    }
+
+    #[test]
+    fn names_profiles_after_the_broadcast_ssid() {
+        assert_eq!(wpa("My Net", "secret").profile_name(), "My Net.psk");
+        assert_eq!(wpa("Café", "secret").profile_name(), "=436166c3a9.psk");
+        // Hex digits which decode to UTF-8 are an SSID of their own.
+        assert_eq!(wpa("4142", "secret").ssid_bytes(), b"4142");
+        assert_eq!(wpa("4142", "secret").profile_name(), "4142.psk");
+
+        let qr = WifiQr::from_str("WIFI:T:WPA2-EAP;S:ff00c0;E:PWD;I:user;P:secret;;").unwrap();
+        assert_eq!(qr.ssid_bytes(), [0xff, 0x00, 0xc0]);
+        assert_eq!(qr.profile_name(), "=ff00c0.8021x");
+        assert!(qr.profile().is_ok());
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        let eap = WifiQr {
+            ssid: "Corp".to_string(),
+            authentication: WifiAuthentication::Wpa2Eap(EapCredentials {
+                method: Some("TTLS".to_string()),
+                anonymous_identity: Some("anonymous".to_string()),
+                identity: Some("user@corp".to_string()),
+                phase2_method: Some("PAP".to_string()),
+            }),
+            password: Some("pass;word".to_string()),
+            hidden: true,
+        };
+        let open = WifiQr {
+            ssid: "Café".to_string(),
+            authentication: WifiAuthentication::Open,
+            password: None,
+            hidden: false,
+        };
+
+        for qr in [
+            wpa("MyNet", "secret"),
+            wpa("4142", "secret"),
+            wpa("\"quoted\"", "a;b:c,d\\e"),
+            eap,
+            open,
+        ] {
+            assert_eq!(WifiQr::from_str(&qr.to_string()), Ok(qr));
+        }
+        assert_eq!(
+            wpa("My;Net", "secret").to_string(),
+            r"WIFI:T:WPA;S:My\;Net;P:secret;;"
+        );
+    }
+
+    #[test]
+    fn escapes_profile_values() {
+        assert_eq!(
+            wpa("MyNet", " back\\slash").profile().unwrap(),
+            "[Security]\nPassphrase=\\sback\\\\slash\n"
+        );
+        assert!(wpa("MyNet", "nul\0").profile().is_err());
+    }
+
+    #[test]
+    fn writes_the_keys_of_the_eap_method() {
+        let eap = |method: &str| WifiQr {
+            ssid: "Corp".to_string(),
+            authentication: WifiAuthentication::Wpa2Eap(EapCredentials {
+                method: Some(method.to_string()),
+                anonymous_identity: Some("anonymous".to_string()),
+                identity: Some("user".to_string()),
+                phase2_method: None,
+            }),
+            password: Some("pass\nword".to_string()),
+            hidden: false,
+        };
+
+        assert_eq!(
+            eap("peap").profile().unwrap(),
+            "[Security]\nEAP-Method=PEAP\nEAP-Identity=anonymous\nEAP-PEAP-Phase2-Method=MSCHAPV2\n\
+             EAP-PEAP-Phase2-Identity=user\nEAP-PEAP-Phase2-Password=pass\\nword\n"
+        );
+        assert_eq!(
+            eap("PWD").profile().unwrap(),
+            "[Security]\nEAP-Method=PWD\nEAP-PWD-Identity=user\nEAP-PWD-Password=pass\\nword\n"
+        );
+        assert!(eap("TLS").profile().is_err());
+    }
+
+    #[test]
+    fn names_ttls_inner_methods_like_iwd() {
+        let ttls = |phase2_method: Option<&str>| WifiQr {
+            ssid: "Corp".to_string(),
+            authentication: WifiAuthentication::Wpa2Eap(EapCredentials {
+                method: Some("TTLS".to_string()),
+                anonymous_identity: None,
+                identity: Some("user".to_string()),
+                phase2_method: phase2_method.map(str::to_string),
+            }),
+            password: Some("secret".to_string()),
+            hidden: false,
+        };
+
+        assert_eq!(
+            ttls(Some("PAP")).profile().unwrap(),
+            "[Security]\nEAP-Method=TTLS\nEAP-TTLS-Phase2-Method=Tunneled-PAP\n\
+             EAP-TTLS-Phase2-Identity=user\nEAP-TTLS-Phase2-Password=secret\n"
+        );
+        for (phase2_method, name) in [
+            (None, "Tunneled-MSCHAPv2"),
+            (Some("chap"), "Tunneled-CHAP"),
+            (Some("MSCHAP"), "Tunneled-MSCHAP"),
+            (Some("MSCHAPV2"), "Tunneled-MSCHAPv2"),
+            (Some("GTC"), "GTC"),
+        ] {
+            assert!(
+                ttls(phase2_method)
+                    .profile()
+                    .unwrap()
+                    .contains(&format!("EAP-TTLS-Phase2-Method={name}\n")),
+                "{phase2_method:?}"
+            );
+        }
+        assert!(ttls(Some("SIM")).profile().is_err());
+    }
+}